@@ -0,0 +1,37 @@
+//! `cfg(tia_local_file)` if the toolchain has `proc_macro::Span::local_file` (stable since 1.88), see `generated_file_path`.
+//! The older toolchains are also supported, the generated files are in the flat layout `{crate}/{TargetTypeSymbol}.rs` with them.
+fn main()
+{
+ println!("cargo:rerun-if-changed=build.rs");
+
+ let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+ // `rustc 1.88.0 (6b00bc388 2025-06-23)` => `(1, 88)`
+ let version = std::process::Command::new(rustc)
+  .arg("--version")
+  .output()
+  .ok()
+  .and_then(|output| String::from_utf8(output.stdout).ok())
+  .and_then(|version| {
+   let mut numbers = version
+    .split(|c: char| c == ' ' || c == '.')
+    .skip(1)
+    .map(|number| {
+     number
+      .parse::<u32>()
+      .ok()
+    });
+   Some((numbers.next()??, numbers.next()??))
+  });
+ if let Some((1, minor)) = version
+ {
+  // `cargo:rustc-check-cfg` is stable since 1.80, the older cargo warns it.
+  if minor >= 80
+  {
+   println!("cargo:rustc-check-cfg=cfg(tia_local_file)");
+  }
+  if minor >= 88
+  {
+   println!("cargo:rustc-cfg=tia_local_file");
+  }
+ }
+}
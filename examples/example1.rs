@@ -14,7 +14,7 @@ fn main()
   foo: 123,
   bar: "Hello".into()
  };
- let foo = mys.get_foo(); // <-- 4. !! generated by tia automatically !!
- let bar = mys.get_bar(); // <-- 5. !! generated by tia automatically !!
- println!("foo={} bar={}", foo, bar);
+ let foo_value = mys.get_foo(); // <-- 4. !! generated by tia automatically !!
+ let bar_value = mys.get_bar(); // <-- 5. !! generated by tia automatically !!
+ println!("foo={} bar={}", foo_value, bar_value);
 }
@@ -28,9 +28,9 @@ fn main()
  // rmg; reference-mut-getter
  // with per-field level directive overwriting.
  {
-  let foo = mys.get_foo(); // <-- &mut i32
-  *foo = 42;
-  dbg!(&foo);
+  let foo_ref = mys.get_foo(); // <-- &mut i32
+  *foo_ref = 42;
+  dbg!(&foo_ref);
   dbg!(&mys);
  }
 
@@ -0,0 +1,846 @@
+//! `cargo tia`; expand, check, list and clean the generated files of `#[derive(Tia)]` without compiling the crate.
+//!
+//! ```sh
+//! cargo install tia
+//! cargo tia expand # Write all generated files to `out_dir` of `tia.toml`, default: `src/.tia`.
+//! cargo tia check  # Exit with an error if a generated file is missing, stale or orphaned. It is useful in CI.
+//! cargo tia list   # Show the generated methods per type.
+//! cargo tia clean  # Remove the orphaned generated files.
+//! ```
+//!
+//! The parser and the generator are same as the proc-macro, thus the input hash in the header of the generated files are same as the build.
+
+extern crate proc_macro;
+
+use quote::ToTokens;
+use std::{
+ collections::HashSet,
+ path::{
+  Path,
+  PathBuf
+ },
+ process::exit,
+ sync::Arc
+};
+
+/// The modules of the proc-macro crate, `src/detail.rs` and `src/detail/*`.
+#[allow(dead_code, unused_imports)]
+#[path = ".."]
+mod tia_src
+{
+ pub mod detail;
+}
+
+use tia_src::detail::{
+ config,
+ output,
+ Tia
+};
+
+const USAGE: &str = r#"cargo tia; expand, check, list and clean the generated files of `#[derive(Tia)]`
+
+Usage: cargo tia <COMMAND> [--manifest-path <PATH>]
+
+Commands:
+  expand  Write all generated files
+  check   Exit with an error if a generated file is missing, stale or orphaned
+  list    Show the generated methods per type
+  clean   Remove the orphaned generated files"#;
+
+const SUBCOMMAND: &str = "tia";
+const MANIFEST_PATH_OPTION: &str = "--manifest-path";
+/// The panics of tia such as a syntax error have the code, eg. `#TIA-PANIC-1056`.
+const TIA_PANIC: &str = "#TIA-PANIC-";
+const CARGO_MANIFEST_FILE: &str = "Cargo.toml";
+/// The kind of the target of the build script, it has no types of the package.
+const CUSTOM_BUILD_KIND: &str = "custom-build";
+const UNSUPPORTED_CFG: &str = "the type has `cfg` or `cfg_attr`, build the crate with the `file` feature or `TIA_EMIT_FILE` to generate it";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Command
+{
+ Expand,
+ Check,
+ List,
+ Clean
+}
+
+/// A crate of the package such as the lib, a bin or a test; a target of `cargo metadata`.
+struct Crate
+{
+ /// `CARGO_CRATE_NAME`, eg. `my_crate` of `my-crate`.
+ name: String,
+ /// The crate root such as `src/lib.rs`.
+ root: PathBuf
+}
+
+/// A type with `#[derive(Tia)]` and its generated file.
+struct Target
+{
+ /// Relative to the manifest directory, eg. `src/api/config.rs`.
+ source_file:        PathBuf,
+ target_type_symbol: String,
+ file:               output::GeneratedFile,
+ /// `None` if the type has `cfg` or `cfg_attr`; the compiler evaluates them before the proc-macro, but `cargo tia` could not.
+ tia:                Option<Tia>
+}
+
+fn main()
+{
+ // `cargo tia expand` runs `cargo-tia tia expand`.
+ let args = std::env::args()
+  .skip(1)
+  .collect::<Vec<String>>();
+ let args = match args.first()
+ {
+  Some(arg) if arg == SUBCOMMAND => &args[1..],
+  _ => &args[..]
+ };
+ let (command, manifest_path) = match parse_args(args)
+ {
+  Ok(parsed) => parsed,
+  Err(e) =>
+  {
+   eprintln!("error: {}\n\n{}", e, USAGE);
+   exit(1);
+  }
+ };
+ match run(command, manifest_path)
+ {
+  Ok(true) => exit(0),
+  Ok(false) => exit(1),
+  Err(e) =>
+  {
+   eprintln!("error: {}", e);
+   exit(1)
+  }
+ }
+}
+
+fn parse_args(args: &[String]) -> Result<(Command, Option<PathBuf>), String>
+{
+ let mut command = None;
+ let mut manifest_path = None;
+ let mut args = args.iter();
+ while let Some(arg) = args.next()
+ {
+  match arg.as_str()
+  {
+   "expand" => command = Some(Command::Expand),
+   "check" => command = Some(Command::Check),
+   "list" => command = Some(Command::List),
+   "clean" => command = Some(Command::Clean),
+   MANIFEST_PATH_OPTION =>
+   {
+    let path = args
+     .next()
+     .ok_or_else(|| format!("`{}` requires a path", MANIFEST_PATH_OPTION))?;
+    manifest_path = Some(PathBuf::from(path));
+   },
+   arg => return Err(format!("unknown argument `{}`", arg))
+  }
+ }
+ command
+  .map(|command| (command, manifest_path))
+  .ok_or_else(|| "a command is required".to_string())
+}
+
+/// `Ok(false)` if `check` found a problem or a type could not be generated.
+fn run(command: Command, manifest_path: Option<PathBuf>) -> Result<bool, String>
+{
+ let manifest_dir = find_manifest_dir(manifest_path)?;
+ let crates = read_crates(&manifest_dir)?;
+ // The parser panics with `#TIA-PANIC-xxxx` for a syntax error, they are collected as the errors by `catch` instead.
+ // The other panics such as a bug of `cargo tia` are reported by the previous hook.
+ let previous_hook = std::panic::take_hook();
+ std::panic::set_hook(Box::new(move |info| {
+  if !panic_message(info.payload()).contains(TIA_PANIC)
+  {
+   previous_hook(info)
+  }
+ }));
+
+ // The config and the paths of the generated files are resolved from the manifest directory as same as the proc-macro.
+ let config = catch(|| config::config_of(&manifest_dir))?;
+ let out_dir = catch(|| output::resolve_out_dir(&config))?;
+ let (targets, errors) = collect_targets(&manifest_dir, &crates, &config);
+ for error in &errors
+ {
+  eprintln!("error: {}", error);
+ }
+
+ match command
+ {
+  Command::Expand =>
+  {
+   for target in &targets
+   {
+    match &target.tia
+    {
+     Some(tia) =>
+     {
+      catch(|| {
+       output::write_file(
+        &target.target_type_symbol,
+        &target.file,
+        &tia.input_hash,
+        &tia.impl_definitions(),
+        &tia.config
+       )
+      })?
+     },
+     None =>
+     {
+      eprintln!(
+       "skipped {} ({}): {}",
+       target.target_type_symbol,
+       target
+        .source_file
+        .display(),
+       UNSUPPORTED_CFG
+      )
+     }
+    }
+   }
+   Ok(errors.is_empty())
+  },
+  Command::Check =>
+  {
+   let mut is_ok = errors.is_empty();
+   for target in &targets
+   {
+    let state = match &target.tia
+    {
+     None => "unsupported",
+     Some(tia) =>
+     {
+      match output::include_state(
+       &target
+        .file
+        .path,
+       &tia.input_hash
+      )
+      {
+       output::IncludeState::UpToDate => "ok",
+       output::IncludeState::Stale(_) | output::IncludeState::NoInputHash => "stale",
+       output::IncludeState::NotFound => "missing"
+      }
+     }
+    };
+    is_ok &= state == "ok" || state == "unsupported";
+    println!(
+     "{:<11} {} ({}) => {}",
+     state,
+     target.target_type_symbol,
+     target
+      .source_file
+      .display(),
+     target
+      .file
+      .path
+      .display()
+    );
+   }
+   if targets
+    .iter()
+    .any(|target| {
+     target
+      .tia
+      .is_none()
+    })
+   {
+    println!("note: `unsupported` is {}", UNSUPPORTED_CFG);
+   }
+   for orphan in find_orphans(&out_dir, &targets)
+   {
+    is_ok = false;
+    println!("{:<11} {}", "orphan", orphan.display());
+   }
+   Ok(is_ok)
+  },
+  Command::List =>
+  {
+   for target in &targets
+   {
+    println!(
+     "{} ({})",
+     target.target_type_symbol,
+     target
+      .source_file
+      .display()
+    );
+    match &target.tia
+    {
+     Some(tia) =>
+     {
+      for (item, methods) in list_methods(&tia.impl_definitions())
+      {
+       println!("  {}", item);
+       for method in methods
+       {
+        println!("    fn {}", method);
+       }
+      }
+     },
+     None => println!("  unsupported; {}", UNSUPPORTED_CFG)
+    }
+   }
+   Ok(errors.is_empty())
+  },
+  Command::Clean =>
+  {
+   // A type which could not be parsed has a generated file too, it should not be removed.
+   if !errors.is_empty()
+   {
+    return Err("the orphaned files are not removed, fix the errors above please".to_string());
+   }
+   for orphan in find_orphans(&out_dir, &targets)
+   {
+    std::fs::remove_file(&orphan).map_err(|e| format!("`{}` could not be removed. ({})", orphan.display(), e))?;
+    println!("removed {}", orphan.display());
+    remove_empty_dirs(&out_dir, orphan.parent());
+   }
+   Ok(true)
+  }
+ }
+}
+
+/// `--manifest-path` or `Cargo.toml` of the current directory or its parents.
+fn find_manifest_dir(manifest_path: Option<PathBuf>) -> Result<PathBuf, String>
+{
+ match manifest_path
+ {
+  Some(manifest_path) =>
+  {
+   let manifest_path = manifest_path
+    .canonicalize()
+    .map_err(|e| format!("`{}` is not found. ({})", manifest_path.display(), e))?;
+   manifest_path
+    .parent()
+    .map(Path::to_path_buf)
+    .ok_or_else(|| format!("`{}` has no parent directory", manifest_path.display()))
+  },
+  None =>
+  {
+   let current_dir = std::env::current_dir().map_err(|e| e.to_string())?;
+   current_dir
+    .ancestors()
+    .find(|dir| {
+     dir
+      .join(CARGO_MANIFEST_FILE)
+      .is_file()
+    })
+    .map(Path::to_path_buf)
+    .ok_or_else(|| {
+     format!(
+      "`{}` is not found in `{}` or its parents",
+      CARGO_MANIFEST_FILE,
+      current_dir.display()
+     )
+    })
+  }
+ }
+}
+
+/// The crates of the package by `cargo metadata`, thus the custom names such as `[[test]] name = "suite"` are same as `CARGO_CRATE_NAME`.
+fn read_crates(manifest_dir: &Path) -> Result<Vec<Crate>, String>
+{
+ let manifest_path = manifest_dir.join(CARGO_MANIFEST_FILE);
+ let output = std::process::Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+  .args(["metadata", "--no-deps", "--format-version", "1", MANIFEST_PATH_OPTION])
+  .arg(&manifest_path)
+  .output()
+  .map_err(|e| format!("`cargo metadata` could not be run. ({})", e))?;
+ if !output
+  .status
+  .success()
+ {
+  return Err(format!(
+   "`cargo metadata` of `{}` failed. ({})",
+   manifest_path.display(),
+   String::from_utf8_lossy(&output.stderr).trim()
+  ));
+ }
+ let metadata = serde_json::from_slice::<serde_json::Value>(&output.stdout)
+  .map_err(|e| format!("`cargo metadata` of `{}` could not be parsed. ({})", manifest_path.display(), e))?;
+
+ // A workspace lists all members, the package is found by its manifest.
+ let is_same_file = |a: &Path, b: &Path| {
+  match (a.canonicalize(), b.canonicalize())
+  {
+   (Ok(a), Ok(b)) => a == b,
+   _ => a == b
+  }
+ };
+ let package = metadata["packages"]
+  .as_array()
+  .into_iter()
+  .flatten()
+  .find(|package| {
+   package["manifest_path"]
+    .as_str()
+    .map(|path| is_same_file(Path::new(path), &manifest_path))
+    .unwrap_or_default()
+  })
+  .ok_or_else(|| {
+   format!(
+    "`{}` has no `[package]`, a workspace should be checked for each member with `--manifest-path` please",
+    manifest_path.display()
+   )
+  })?;
+ let package_dir = package["manifest_path"]
+  .as_str()
+  .and_then(|path| Path::new(path).parent());
+ Ok(
+  package["targets"]
+   .as_array()
+   .into_iter()
+   .flatten()
+   .filter(|target| {
+    !target["kind"]
+     .as_array()
+     .into_iter()
+     .flatten()
+     .any(|kind| kind == CUSTOM_BUILD_KIND)
+   })
+   .filter_map(|target| {
+    // The root is under `manifest_dir` as is, thus the paths of the generated files are relative to it even if `cargo` resolves the symlinks.
+    let root = Path::new(target["src_path"].as_str()?);
+    let root = package_dir
+     .and_then(|package_dir| {
+      root
+       .strip_prefix(package_dir)
+       .ok()
+     })
+     .map(|relative_root| manifest_dir.join(relative_root))
+     .unwrap_or_else(|| root.to_path_buf());
+    Some(Crate {
+     name: target["name"]
+      .as_str()?
+      .replace('-', "_"),
+     root
+    })
+   })
+   .collect()
+ )
+}
+
+/// The source files of the crate from the root, the modules such as `mod foo;` are followed recursively.
+/// A file could be in some crates, eg. `tests/common/mod.rs` of `mod common;` in some tests.
+fn find_crate_files(root: &Path) -> Vec<PathBuf>
+{
+ let mut files = vec![];
+ let directory = root
+  .parent()
+  .unwrap_or_else(|| Path::new(""));
+ find_module_files(root, directory, &mut files);
+ files
+}
+
+/// `file` and the files of its modules; `directory` is the directory of the modules declared in `file`.
+fn find_module_files(file: &Path, directory: &Path, files: &mut Vec<PathBuf>)
+{
+ if files
+  .iter()
+  .any(|found| found == file)
+ {
+  return;
+ }
+ files.push(file.to_path_buf());
+ // The errors are reported by `collect_targets`.
+ if let Some(parsed) = std::fs::read_to_string(file)
+  .ok()
+  .and_then(|source| syn::parse_file(&source).ok())
+ {
+  let file_directory = file
+   .parent()
+   .unwrap_or_else(|| Path::new(""));
+  find_declared_module_files(&parsed.items, directory, file_directory, files);
+ }
+}
+
+/// `mod foo;` => `foo.rs` or `foo/mod.rs` in `directory`, `#[path = "bar.rs"] mod foo;` => `bar.rs` in `file_directory`.
+/// The modules in `mod foo { ... }` are in `foo/` of `directory`. The `cfg` of the modules are not evaluated, the existing files are used.
+fn find_declared_module_files(items: &[syn::Item], directory: &Path, file_directory: &Path, files: &mut Vec<PathBuf>)
+{
+ for item in items
+ {
+  let item_mod = match item
+  {
+   syn::Item::Mod(item_mod) => item_mod,
+   _ => continue
+  };
+  let name = item_mod
+   .ident
+   .to_string()
+   .trim_start_matches("r#")
+   .to_string();
+  let path = item_mod
+   .attrs
+   .iter()
+   .find(|attr| {
+    attr
+     .path
+     .is_ident("path")
+   })
+   .and_then(|attr| {
+    match attr.parse_meta()
+    {
+     Ok(syn::Meta::NameValue(syn::MetaNameValue {
+      lit: syn::Lit::Str(path), ..
+     })) => Some(path.value()),
+     _ => None
+    }
+   });
+  match (&item_mod.content, path)
+  {
+   (Some((_, items)), Some(path)) => find_declared_module_files(items, &directory.join(&path), &directory.join(path), files),
+   (Some((_, items)), None) =>
+   {
+    let directory = directory.join(&name);
+    find_declared_module_files(items, &directory, &directory, files)
+   },
+   // A file of `#[path]` is same as `mod.rs`, its modules are in its directory.
+   (None, Some(path)) =>
+   {
+    let module_file = file_directory.join(path);
+    let module_directory = module_file
+     .parent()
+     .unwrap_or_else(|| Path::new(""))
+     .to_path_buf();
+    find_module_files(&module_file, &module_directory, files)
+   },
+   (None, None) =>
+   {
+    let candidates = [
+     (directory.join(format!("{}.rs", name)), directory.join(&name)),
+     (
+      directory
+       .join(&name)
+       .join("mod.rs"),
+      directory.join(&name)
+     )
+    ];
+    if let Some((module_file, module_directory)) = candidates
+     .iter()
+     .find(|(module_file, _)| module_file.is_file())
+    {
+     find_module_files(module_file, module_directory, files)
+    }
+   }
+  }
+ }
+}
+
+/// All types with `#[derive(Tia)]` in the crates of the package, and the errors of the files or the types.
+fn collect_targets(manifest_dir: &Path, crates: &[Crate], config: &Arc<config::Config>) -> (Vec<Target>, Vec<String>)
+{
+ let mut targets = vec![];
+ let mut errors = vec![];
+ for (crate_name, source_file) in crates
+  .iter()
+  .flat_map(|crate_| {
+   find_crate_files(&crate_.root)
+    .into_iter()
+    .map(move |source_file| (&crate_.name, source_file))
+  })
+ {
+  let relative_source_file = source_file
+   .strip_prefix(manifest_dir)
+   .unwrap_or(&source_file)
+   .to_path_buf();
+  let file = match std::fs::read_to_string(&source_file)
+   .map_err(|e| e.to_string())
+   .and_then(|source| syn::parse_file(&source).map_err(|e| e.to_string()))
+  {
+   Ok(file) => file,
+   Err(e) =>
+   {
+    errors.push(format!("`{}` could not be parsed. ({})", relative_source_file.display(), e));
+    continue;
+   }
+  };
+  let mut inputs = vec![];
+  output::collect_derive_inputs(&file.items, &[], &mut inputs);
+  for (_, input) in inputs
+  {
+   let target_type_symbol = input
+    .ident
+    .to_string();
+   let generated_file = match catch(|| {
+    // Resolved by the input hash as same as the proc-macro, the identical same-named types share the first one.
+    let input_hash = output::input_hash(&input, config);
+    let (inline_modules, owner) = match output::inline_module_path(&file, &target_type_symbol, &input_hash, config)
+    {
+     Ok(inline_modules) =>
+     {
+      (
+       inline_modules,
+       relative_source_file
+        .display()
+        .to_string()
+      )
+     },
+     Err(inline_modules) => (inline_modules, format!("{}#{}", relative_source_file.display(), input_hash))
+    };
+    output::GeneratedFile {
+     path: output::generated_file_path_of(
+      config,
+      crate_name,
+      Some(&relative_source_file),
+      &inline_modules,
+      &target_type_symbol
+     ),
+     owner
+    }
+   })
+   {
+    Ok(generated_file) => generated_file,
+    Err(e) =>
+    {
+     errors.push(format!("{} ({}): {}", target_type_symbol, relative_source_file.display(), e));
+     continue;
+    }
+   };
+   let tia = match has_cfg(&input)
+   {
+    true => None,
+    false =>
+    {
+     match catch(|| Tia::new_with(input, config.clone())).and_then(|tia| tia.map_err(|e| e.to_string()))
+     {
+      Ok(tia)
+       if tia
+        .type_params
+        .disabled =>
+      {
+       continue
+      },
+      Ok(tia) => Some(tia),
+      Err(e) =>
+      {
+       errors.push(format!("{} ({}): {}", target_type_symbol, relative_source_file.display(), e));
+       continue;
+      }
+     }
+    },
+   };
+   targets.push(Target {
+    source_file: relative_source_file.clone(),
+    target_type_symbol,
+    file: generated_file,
+    tia
+   });
+  }
+ }
+ (targets, errors)
+}
+
+/// `#[cfg(...)]` or `#[cfg_attr(...)]` on the type, its fields or its variants.
+fn has_cfg(input: &syn::DeriveInput) -> bool
+{
+ let is_cfg = |attrs: &[syn::Attribute]| {
+  attrs
+   .iter()
+   .any(|attr| {
+    attr
+     .path
+     .is_ident("cfg")
+     || attr
+      .path
+      .is_ident("cfg_attr")
+   })
+ };
+ let fields = match &input.data
+ {
+  syn::Data::Struct(data) =>
+  {
+   data
+    .fields
+    .iter()
+    .collect::<Vec<&syn::Field>>()
+  },
+  syn::Data::Union(data) =>
+  {
+   data
+    .fields
+    .named
+    .iter()
+    .collect()
+  },
+  syn::Data::Enum(data) =>
+  {
+   if data
+    .variants
+    .iter()
+    .any(|variant| is_cfg(&variant.attrs))
+   {
+    return true;
+   }
+   data
+    .variants
+    .iter()
+    .flat_map(|variant| {
+     variant
+      .fields
+      .iter()
+    })
+    .collect()
+  }
+ };
+ is_cfg(&input.attrs)
+  || fields
+   .iter()
+   .any(|field| is_cfg(&field.attrs))
+}
+
+/// `impl Trait for MyStruct` => `[get_foo, set_foo]` of the generated code, and the trait declarations of `trait_definition`.
+fn list_methods(impl_definitions: &str) -> Vec<(String, Vec<String>)>
+{
+ let file = match syn::parse_file(impl_definitions)
+ {
+  Ok(file) => file,
+  Err(_) => return vec![]
+ };
+ let compact = |tokens: &dyn ToTokens| {
+  tokens
+   .to_token_stream()
+   .to_string()
+   .replace(' ', "")
+ };
+ file
+  .items
+  .iter()
+  .filter_map(|item| {
+   match item
+   {
+    syn::Item::Impl(item_impl) =>
+    {
+     let header = match &item_impl.trait_
+     {
+      Some((_, path, _)) => format!("impl {} for {}", compact(path), compact(&item_impl.self_ty)),
+      None => format!("impl {}", compact(&item_impl.self_ty))
+     };
+     let methods = item_impl
+      .items
+      .iter()
+      .filter_map(|item| {
+       match item
+       {
+        syn::ImplItem::Method(method) =>
+        {
+         Some(
+          method
+           .sig
+           .ident
+           .to_string()
+         )
+        },
+        _ => None
+       }
+      })
+      .collect();
+     Some((header, methods))
+    },
+    syn::Item::Trait(item_trait) =>
+    {
+     let methods = item_trait
+      .items
+      .iter()
+      .filter_map(|item| {
+       match item
+       {
+        syn::TraitItem::Method(method) =>
+        {
+         Some(
+          method
+           .sig
+           .ident
+           .to_string()
+         )
+        },
+        _ => None
+       }
+      })
+      .collect();
+     Some((format!("trait {}", item_trait.ident), methods))
+    },
+    _ => None
+   }
+  })
+  .collect()
+}
+
+/// The generated files in `out_dir` which are not generated by any types; the files without the input hash header are not tia's.
+fn find_orphans(out_dir: &Path, targets: &[Target]) -> Vec<PathBuf>
+{
+ let paths = targets
+  .iter()
+  .map(|target| {
+   target
+    .file
+    .path
+    .as_path()
+  })
+  .collect::<HashSet<&Path>>();
+ find_generated_files(out_dir)
+  .into_iter()
+  .filter(|path| !paths.contains(path.as_path()) && output::read_input_hash(path).is_some())
+  .collect()
+}
+
+fn find_generated_files(dir: &Path) -> Vec<PathBuf>
+{
+ let mut entries = std::fs::read_dir(dir)
+  .map(|entries| {
+   entries
+    .filter_map(Result::ok)
+    .map(|entry| entry.path())
+    .collect::<Vec<PathBuf>>()
+  })
+  .unwrap_or_default();
+ entries.sort();
+ entries
+  .into_iter()
+  .flat_map(|path| {
+   match path.is_dir()
+   {
+    true => find_generated_files(&path),
+    false => vec![path]
+   }
+  })
+  .collect()
+}
+
+/// Remove `dir` and its parents while they are empty, `out_dir` itself is kept.
+fn remove_empty_dirs(out_dir: &Path, dir: Option<&Path>)
+{
+ let mut dir = dir;
+ while let Some(current) = dir
+ {
+  if current == out_dir || !current.starts_with(out_dir) || std::fs::remove_dir(current).is_err()
+  {
+   break;
+  }
+  dir = current.parent();
+ }
+}
+
+/// Run `f` and convert a panic such as `#TIA-PANIC-1049` to an error message.
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String>
+{
+ std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String
+{
+ payload
+  .downcast_ref::<String>()
+  .cloned()
+  .or_else(|| {
+   payload
+    .downcast_ref::<&str>()
+    .map(|message| message.to_string())
+  })
+  .unwrap_or_else(|| "tia panicked".to_string())
+}
@@ -1,6 +1,15 @@
+pub mod config;
 mod generator;
+pub mod output;
 mod parser;
 
+pub use output::{
+ generated_file,
+ include_state,
+ input_hash,
+ IncludeState
+};
+
 use proc_macro as pm;
 use std::collections::{
  HashMap,
@@ -9,13 +18,24 @@ use std::collections::{
 
 const EMPTY_STR: &str = "";
 use self::EMPTY_STR as NO_SEPARATOR;
+const COMMA: &str = ",";
+const TIA_PRINT: &str = "TIA_PRINT";
+const TIA_EMIT_FILE: &str = "TIA_EMIT_FILE";
 
 #[derive(Debug)]
 pub struct Tia
 {
- target_type_symbol:       TargetTypeSymbol,
- target_type:              TargetType,
- trait_to_field_accessors: TraitToFieldAccessors
+ pub(crate) target_type_symbol: TargetTypeSymbol,
+ target_type:                   TargetType,
+ /// eg. `pub(crate) `, it will be used for the generated types such as `MyStructField`.
+ target_visibility:             String,
+ pub(crate) type_params:        TypeParams,
+ target_fields:                 Vec<TargetField>,
+ trait_to_field_accessors:      TraitToFieldAccessors,
+ /// `tia.toml` or `[package.metadata.tia]` of the crate.
+ pub(crate) config:             std::sync::Arc<config::Config>,
+ /// The hash of the input `DeriveInput`, it will be written in the header of the generated file.
+ pub(crate) input_hash:         String
 }
 
 pub type TraitToFieldAccessors = HashMap<TraitSymbol, FieldSymbolToFieldParams>;
@@ -24,6 +44,7 @@ pub type TargetTypeSymbol = String;
 pub type FieldSymbol = String;
 pub type FieldType = String;
 pub type TraitSymbol = String;
+pub type TraitSymbolToTraitDefinition = HashMap<TraitSymbol, TraitDefinition>;
 
 #[derive(Debug)]
 pub enum TargetType
@@ -39,12 +60,20 @@ pub enum Accessor
  Setter
  {
   fsp: FieldSymbolPolicy,
-  ptp: SetterParameterTypePolicy
+  ptp: SetterParameterTypePolicy,
+  svp: Option<SetterValidationPolicy>
  },
  Getter
  {
   fsp: FieldSymbolPolicy,
   ptp: GetterParameterTypePolicy
+ },
+ /// `delegate_fn="fn len(&self) -> usize"` => `fn len(&self) -> usize { self.field.len() }`
+ Delegate
+ {
+  fn_symbol: String,
+  signature: String,
+  arguments: String
  }
 }
 
@@ -56,24 +85,28 @@ impl PartialEq for Accessor
   {
    (
     Accessor::Getter {
-     fsp: _,
-     ptp: _
+     ..
     },
     Accessor::Getter {
-     fsp: _,
-     ptp: _
+     ..
     }
    )
    | (
     Accessor::Setter {
-     fsp: _,
-     ptp: _
+     ..
     },
     Accessor::Setter {
-     fsp: _,
-     ptp: _
+     ..
     }
    ) => true,
+   (
+    Accessor::Delegate {
+     fn_symbol: a, ..
+    },
+    Accessor::Delegate {
+     fn_symbol: b, ..
+    }
+   ) => a == b,
    _ => false
   }
  }
@@ -86,13 +119,18 @@ impl std::hash::Hash for Accessor
   match self
   {
    Accessor::Getter {
-    fsp: _,
-    ptp: _
+    ..
    } => 0.hash(h),
    Accessor::Setter {
-    fsp: _,
-    ptp: _
-   } => 1.hash(h)
+    ..
+   } => 1.hash(h),
+   Accessor::Delegate {
+    fn_symbol, ..
+   } =>
+   {
+    2.hash(h);
+    fn_symbol.hash(h)
+   }
   }
  }
 }
@@ -103,7 +141,11 @@ pub enum GetterParameterTypePolicy
  Move,
  Value,
  Ref,
- RefMut
+ RefMut,
+ /// `rgc` => `fn get_foo(&self) -> T { self.foo.clone() }`
+ Clone,
+ /// `gi(ty=u64)` => `fn get_foo(&self) -> u64 { self.foo.clone().into() }`
+ Into(FieldType)
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -111,7 +153,27 @@ pub enum SetterParameterTypePolicy
 {
  Value,
  RefClone,
- Into
+ Into,
+ /// `rsti` => `fn set_foo<T: TryInto<u8>>(&mut self, v: T) -> Result<(), T::Error>`
+ TryInto,
+ /// `rsa` => `fn set_foo<T: AsRef<Path>>(&mut self, v: T)` for `PathBuf` field; `rsa(ty=str)` overrides the `Deref::Target` default.
+ AsRef(Option<FieldType>),
+ /// `rsp` => `fn set_foo_str(&mut self, v: &str) -> Result<(), <T as FromStr>::Err>`
+ Parse
+}
+
+/// `s(validate="check", min=1, max=100, len="1..=64", error="MyError")`, the setter will return `Result<(), MyError>`.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SetterValidationPolicy
+{
+ /// A path to `fn(&T) -> Result<(), E>`; `E` should be `Into<error>`.
+ pub validate: Option<String>,
+ pub min:      Option<String>,
+ pub max:      Option<String>,
+ /// A range such as `1..=64` or an exact length such as `8`.
+ pub len:      Option<String>,
+ /// The error type, `String` if not specified. It should be `From<&'static str>` if the built-in checks are used.
+ pub error:    Option<String>
 }
 
 #[allow(dead_code)]
@@ -121,84 +183,351 @@ pub enum FieldSymbolPolicy
  Default,
  Prefix(String),
  Suffix(String),
- Fullname(String)
+ Fullname(String),
+ Bare
+}
+
+/// Naming style of the accessors that use the default naming policy.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NamingStyle
+{
+ /// `get_foo`, `set_foo`
+ GetSet,
+ /// `foo`, `foo_mut`, `into_foo`, `set_foo`; the Rust API guidelines style.
+ Bare
+}
+
+impl Default for NamingStyle
+{
+ fn default() -> Self { NamingStyle::GetSet }
+}
+
+/// A `trait` declaration generated by the `define_trait="..."` directive.
+#[derive(Debug, Clone)]
+pub struct TraitDefinition
+{
+ /// eg. `pub trait FooAccess<T: Clone>: Debug where T: Default`
+ pub declaration_header: String,
+ /// eg. `<T: Clone>`
+ pub impl_generics:      String,
+ /// eg. `where T: Default`
+ pub where_clause:       String
+}
+
+/// `on_change="notify"` => `self.notify(MyStructField::Foo, &old, &new)` after the assignment.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OnChange
+{
+ pub method:         String,
+ /// `on_change_if_changed="notify"` => skip the call if `old == new`.
+ pub skip_unchanged: bool
+}
+
+/// `visit_fields` and `visit_fields_mut` generated by the `visitor` directive.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VisitorPolicy
+{
+ /// `visitor` => `v: &mut impl FnMut(&'static str, &dyn Any)`
+ Closure,
+ /// `visitor="path::to::FieldVisitor"` => `v.visit("foo", &self.foo)` and `v.visit_mut("foo", &mut self.foo)`
+ Trait(String)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TypeParams
+{
+ pub naming_style:      NamingStyle,
+ pub trait_definitions: TraitSymbolToTraitDefinition,
+ pub on_change:         Option<OnChange>,
+ /// `dirty` or `dirty="state"` => the field symbol of `MyStructDirty`, eg. `dirty` or `state`.
+ pub dirty:             Option<FieldSymbol>,
+ /// `reflect` => `FIELDS`, `field_names`, `get_by_name` and `set_by_name`.
+ pub reflect:           bool,
+ pub visitor:           Option<VisitorPolicy>,
+ /// `lens` or `lens="crate::state"` => the module path where `tia::lens_types!()` is, eg. `crate` or `crate::state`.
+ pub lens:              Option<String>,
+ pub virtual_accessors: Vec<VirtualAccessor>,
+ /// `"Trait", merge` => the accessors of the trait will be injected into `#[tia::impl_accessors] impl Trait for MyStruct { ... }`.
+ pub merged_traits:     std::collections::BTreeSet<TraitSymbol>,
+ /// `for_type(bool="g", "Vec<_>"="rmg, s")` => the accessors for the fields of the matched types, in the declaration order.
+ pub for_types:         Vec<ForType>,
+ /// `print` => show the generated code of the type to stderr, same as the `print` feature for the type only.
+ pub print:             bool,
+ /// `emit_file` => write the generated code of the type to the file, same as the `file` feature for the type only.
+ pub emit_file:         bool,
+ /// `disabled` => generate nothing for the type, same as the `disable` feature for the type only.
+ pub disabled:          bool,
+ /// `exclude="password, token"` => the fields which ignore the struct|enum|union-level directives, same as `skip` for each field.
+ pub excluded:          Vec<FieldSymbol>
+}
+
+/// `bool="g"` of `for_type(...)`.
+#[derive(Debug, Clone)]
+pub struct ForType
+{
+ /// eg. `bool` or `Vec<_>`; `_` matches any type.
+ pub pattern:   FieldType,
+ pub accessors: Vec<(TraitSymbol, Accessor)>
+}
+
+/// `virtual(g="area", ty="f64", expr="self.w * self.h")` => `fn area(&self) -> f64 { self.w * self.h }`
+#[derive(Debug, Clone)]
+pub struct VirtualAccessor
+{
+ pub trait_symbol: TraitSymbol,
+ /// The getter or the setter with `FieldSymbolPolicy::Fullname`.
+ pub accessor:     Accessor,
+ pub field_type:   FieldType,
+ /// The body; the setters could use `v`.
+ pub expr:         String
+}
+
+/// A field of the target type, in the declaration order.
+#[derive(Debug, Default, Clone)]
+pub struct TargetField
+{
+ pub field_symbol: FieldSymbol,
+ pub field_type:   FieldType,
+ /// eg. `pub `, or ` ` if the field is private.
+ pub visibility:   String,
+ /// `delegate="Display, Iterator"` => `["Display", "Iterator"]`
+ pub delegations:  Vec<String>,
+ pub on_change:    Option<OnChange>,
+ /// `flatten(rg="x: f32")` => the accessors of `self.pos.x` for the field `pos`.
+ pub flattens:     Vec<Flatten>,
+ /// `skip` or `exclude="..."` => the field ignores the struct|enum|union-level directives, and it is hidden from `reflect` and `lens`.
+ pub skip:         bool,
+ /// `skip(g, "Trait")` => `[("", Some(getter)), ("Trait", None)]`, the inherited accessors or traits to remove.
+ pub skipped:      Vec<(TraitSymbol, Option<Accessor>)>
+}
+
+/// An accessor of a member of a field, eg. `rg` of `x: f32` for `self.pos.x`.
+#[derive(Debug, Clone)]
+pub struct Flatten
+{
+ pub trait_symbol: TraitSymbol,
+ pub accessor:     Accessor,
+ /// The path from the field, eg. `x` or `inner.z`.
+ pub path:         String,
+ pub field_type:   FieldType
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct FieldParams
 {
  pub field_type: FieldType,
- pub accessors:  HashSet<Accessor>
+ pub accessors:  HashSet<Accessor>,
+ /// The field-level `on_change` or the struct-level `on_change`.
+ pub on_change:  Option<OnChange>,
+ /// `pos.x` if it is a flattened accessor, the accessors will use `self.pos.x` instead of `self.x`.
+ pub path:       Option<String>
 }
 
 impl Tia
 {
- pub fn new(i: syn::DeriveInput) -> Self { parser::parse(i) }
+ pub fn new(i: syn::DeriveInput) -> syn::Result<Self> { parser::parse(i) }
+
+ /// `new` with the config of a crate, eg. `cargo tia` which is not in the build of the crate.
+ pub fn new_with(i: syn::DeriveInput, config: std::sync::Arc<config::Config>) -> syn::Result<Self> { parser::parse_with(i, config) }
+
+ /// The generated code, it is the content of the generated file without the header.
+ /// It does not contain `config::generate_config_dependency`, the absolute path of `tia.toml` should not be written in the file.
+ pub fn impl_definitions(&self) -> String { generator::generate_impl_definitions(self) }
 }
 
-impl Into<pm::TokenStream> for Tia
+/// The expansion of `#[tia::impl_accessors]`.
+pub fn impl_accessors(item: pm::TokenStream) -> pm::TokenStream
 {
- fn into(self) -> pm::TokenStream
+ let item_impl = match syn::parse::<syn::ItemImpl>(item)
  {
-  let impl_definitions = generator::generate_impl_definitions(&self.trait_to_field_accessors, &self.target_type_symbol, self.target_type);
+  Ok(item_impl) => item_impl,
+  Err(e) =>
+  {
+   return e
+    .to_compile_error()
+    .into()
+  },
+ };
+ match parser::parse_merge_target(&item_impl)
+ {
+  Ok((target_type_symbol, trait_symbol)) => generator::generate_merge_invocation(&target_type_symbol, &trait_symbol, &item_impl).into(),
+  Err(e) =>
+  {
+   e.to_compile_error()
+    .into()
+  },
+ }
+}
+
+/// The expansion of `tia::lens_types!()`.
+pub fn lens_types() -> pm::TokenStream
+{
+ generator::generate_lens_types()
+  .parse::<pm::TokenStream>()
+  .expect("tia::lens_types was failed. #TIA-PANIC-4002")
+}
 
-  if cfg!(feature = "print")
+impl From<Tia> for pm::TokenStream
+{
+ fn from(tia: Tia) -> Self
+ {
+  if tia
+   .type_params
+   .disabled
   {
-   eprintln!("[proc-macro:tia +print Target: {}]", &self.target_type_symbol);
+   return pm::TokenStream::new();
+  }
+
+  let impl_definitions = tia.impl_definitions();
+
+  if tia.is_print_selected()
+  {
+   eprintln!("[proc-macro:tia +print Target: {}]", &tia.target_type_symbol);
    eprintln!("{}", &impl_definitions);
   }
 
-  if cfg!(feature = "file") || cfg!(feature = "file-pretty") || cfg!(feature = "include") || cfg!(feature = "include-pretty")
+  if tia.is_emit_file_selected()
   {
-   write_file(&self.target_type_symbol, &impl_definitions)
+   output::write_file(
+    &tia.target_type_symbol,
+    &generated_file(&tia.target_type_symbol, &tia.input_hash),
+    &tia.input_hash,
+    &impl_definitions,
+    &tia.config
+   )
   };
 
-  impl_definitions
+  (impl_definitions + &config::generate_config_dependency(&tia.config))
    .parse::<pm::TokenStream>()
    .expect("tia::into<proc_macro::TokenStream> was failed. #TIA-PANIC-4001")
  }
 }
 
-fn write_file(target_type_symbol: &String, source: &String)
+impl Tia
 {
- use std::{
-  fs::{
-   create_dir_all,
-   File
-  },
-  io::Write
- };
+ /// The `print` feature, the `print` config key, the `print` directive or `TIA_PRINT`.
+ fn is_print_selected(&self) -> bool
+ {
+  cfg!(feature = "print")
+   || self
+    .config
+    .print
+   || self
+    .type_params
+    .print
+   || is_selected_by_env(TIA_PRINT, &self.target_type_symbol)
+ }
+
+ /// The `file*` and `include*` features, the `file` or `rustfmt` config keys, the `emit_file` directive or `TIA_EMIT_FILE`.
+ fn is_emit_file_selected(&self) -> bool
+ {
+  cfg!(feature = "file")
+   || cfg!(feature = "file-pretty")
+   || cfg!(feature = "include")
+   || cfg!(feature = "include-pretty")
+   || self
+    .config
+    .file
+   || self
+    .config
+    .rustfmt
+   || self
+    .type_params
+    .emit_file
+   || is_selected_by_env(TIA_EMIT_FILE, &self.target_type_symbol)
+ }
+}
+
+/// `TIA_PRINT=MyStruct,Other` or `TIA_PRINT=*` => true if `target_type_symbol` is selected by the env var.
+fn is_selected_by_env(key: &str, target_type_symbol: &str) -> bool
+{
+ std::env::var(key)
+  .map(|value| is_selected(&value, target_type_symbol))
+  .unwrap_or_default()
+}
 
- let out_dir = "src/.tia/";
- create_dir_all(out_dir).unwrap();
+/// `value` is a comma separated list of the type symbols or `*`.
+fn is_selected(value: &str, target_type_symbol: &str) -> bool
+{
+ value
+  .split(COMMA)
+  .map(str::trim)
+  .any(|selected| selected == "*" || selected == target_type_symbol)
+}
 
- let path = format!("{}{}.rs", out_dir, &target_type_symbol);
- let mut file = File::create(&path).unwrap();
- write!(file, "{}", source).unwrap();
+#[cfg(test)]
+mod tests
+{
+ use super::*;
 
- if cfg!(feature = "file-pretty") || cfg!(feature = "include-pretty")
+ fn parse(source: &str, config: config::Config) -> Tia
  {
-  let result = if file_pretty(&path) { "<OK>" } else { "<NG>" };
-  eprintln!(
-   "[proc-macro:tia +file-pretty Target: {} => {} ; rustfmt {} ]",
-   &target_type_symbol, &path, result
-  );
+  parser::parse_with(syn::parse_str(source).unwrap(), std::sync::Arc::new(config)).unwrap()
  }
- else
+
+ #[test]
+ fn selected_by_env_value()
  {
-  eprintln!("[proc-macro:tia +file Target: {} => {}]", &target_type_symbol, &path);
+  assert!(is_selected("*", "Sample"));
+  assert!(is_selected("Sample", "Sample"));
+  assert!(is_selected("Other,Sample", "Sample"));
+  assert!(is_selected(" Other , Sample ", "Sample"));
+  assert!(is_selected("Other, *", "Sample"));
+  assert!(!is_selected("", "Sample"));
+  assert!(!is_selected("Other", "Sample"));
+  assert!(!is_selected("Other,SampleX", "Sample"));
+  assert!(!is_selected("Sam ple", "Sample"));
  }
-}
 
-fn file_pretty(path: &String) -> bool
-{
- std::process::Command::new("rustfmt")
-  .arg("-q")
-  .arg(path)
-  .stdin(std::process::Stdio::null())
-  .stdout(std::process::Stdio::null())
-  .stderr(std::process::Stdio::null())
-  .spawn()
-  .and_then(|mut child_process| child_process.wait())
-  .is_ok()
+ #[test]
+ fn selected_by_env()
+ {
+  // A key unique to this test; the real `TIA_PRINT` might be set by the developer.
+  const KEY: &str = "TIA_TEST_SELECTED_BY_ENV";
+  assert!(!is_selected_by_env(KEY, "Sample"));
+  std::env::set_var(KEY, "Other, Sample");
+  assert!(is_selected_by_env(KEY, "Sample"));
+  assert!(!is_selected_by_env(KEY, "Another"));
+  std::env::remove_var(KEY);
+ }
+
+ #[test]
+ fn print_and_emit_file()
+ {
+  let by_env = is_selected_by_env(TIA_PRINT, "Sample") || is_selected_by_env(TIA_EMIT_FILE, "Sample");
+  let tia = parse("#[tia(rg)] struct Sample { x: i32 }", config::Config::default());
+  if !by_env && !cfg!(feature = "print")
+  {
+   assert!(!tia.is_print_selected());
+  }
+  if !by_env
+   && !cfg!(any(
+    feature = "file",
+    feature = "file-pretty",
+    feature = "include",
+    feature = "include-pretty"
+   ))
+  {
+   assert!(!tia.is_emit_file_selected());
+  }
+
+  // The directives
+  let tia = parse("#[tia(rg, print, emit_file)] struct Sample { x: i32 }", config::Config::default());
+  assert!(tia.is_print_selected());
+  assert!(tia.is_emit_file_selected());
+
+  // The config keys
+  let tia = parse("#[tia(rg)] struct Sample { x: i32 }", config::Config {
+   print: true,
+   file: true,
+   ..Default::default()
+  });
+  assert!(tia.is_print_selected());
+  assert!(tia.is_emit_file_selected());
+  let tia = parse("#[tia(rg)] struct Sample { x: i32 }", config::Config {
+   rustfmt: true,
+   ..Default::default()
+  });
+  assert!(tia.is_emit_file_selected());
+ }
 }
@@ -0,0 +1,506 @@
+use super::*;
+use once_cell::sync::Lazy;
+use std::{
+ path::{
+  Path,
+  PathBuf
+ },
+ sync::{
+  Arc,
+  Mutex
+ }
+};
+
+const CONFIG_FILE: &str = "tia.toml";
+const CARGO_MANIFEST_FILE: &str = "Cargo.toml";
+const DEFAULT_OUT_DIR: &str = "src/.tia";
+
+/// The project configuration; `[package.metadata.tia]` in `Cargo.toml`, and `tia.toml` in `CARGO_MANIFEST_DIR` overrides it.
+#[derive(Debug)]
+pub struct Config
+{
+ /// The path of `tia.toml` if it exists.
+ pub path:          Option<PathBuf>,
+ /// `CARGO_MANIFEST_DIR` of the crate, the root of `out_dir` and the start of the search of `rustfmt.toml`.
+ pub manifest_dir:  PathBuf,
+ /// `edition = "2021"` of `[package]` in `Cargo.toml`, it is `None` if it is not written or inherited from the workspace.
+ pub edition:       Option<String>,
+ /// `[presets] pod = "g, s, rsi"` => `{ "pod": "g, s, rsi" }`
+ pub presets:       HashMap<String, String>,
+ /// `naming = "bare"`, the default of `naming="..."` of each type.
+ pub naming:        NamingStyle,
+ /// `visibility = "pub(crate)"`, the visibility of the accessors of the inherent impl.
+ pub visibility:    String,
+ /// `inline = "always"`, the `#[inline]` attribute of the accessors.
+ pub inline:        InlinePolicy,
+ /// `deterministic = false` => the generated items are not sorted, they follow the order of the hash maps.
+ pub deterministic: bool,
+ /// `print = true`, same as the `print` feature for this crate only.
+ pub print:         bool,
+ /// `file = true`, same as the `file` feature for this crate only.
+ pub file:          bool,
+ /// `rustfmt = true`, same as the `file-pretty` feature with the `rustfmt` formatter for this crate only.
+ pub rustfmt:       bool,
+ /// `formatter = "rustfmt"`, the formatter of `rustfmt = true` and the `*-pretty` features; see `pretty_formatter`.
+ pub formatter:     Option<Formatter>,
+ /// `out_dir = "src/.tia"`, the directory of the `file` and `include` features.
+ pub out_dir:       String
+}
+
+impl Default for Config
+{
+ fn default() -> Self
+ {
+  Config {
+   path:          None,
+   manifest_dir:  PathBuf::new(),
+   edition:       None,
+   presets:       HashMap::new(),
+   naming:        NamingStyle::default(),
+   visibility:    "pub".to_string(),
+   inline:        InlinePolicy::default(),
+   deterministic: true,
+   print:         false,
+   file:          false,
+   rustfmt:       false,
+   formatter:     None,
+   out_dir:       DEFAULT_OUT_DIR.to_string()
+  }
+ }
+}
+
+/// `inline = "never" | "inline" | "always"`
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InlinePolicy
+{
+ /// No attribute, it is the default.
+ Never,
+ /// `#[inline]`
+ Inline,
+ /// `#[inline(always)]`
+ Always
+}
+
+impl Default for InlinePolicy
+{
+ fn default() -> Self { InlinePolicy::Never }
+}
+
+impl InlinePolicy
+{
+ pub fn attribute(&self) -> &'static str
+ {
+  match self
+  {
+   InlinePolicy::Never => "",
+   InlinePolicy::Inline => "#[inline]",
+   InlinePolicy::Always => "#[inline(always)]"
+  }
+ }
+}
+
+/// `formatter = "prettyplease" | "rustfmt"`
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Formatter
+{
+ /// In-process, it does not require any commands.
+ Prettyplease,
+ /// The external `rustfmt` command, it respects `rustfmt.toml` of the project.
+ Rustfmt
+}
+
+/// The configs per `CARGO_MANIFEST_DIR`, a proc-macro server such as rust-analyzer could expand the macros of some crates.
+static CONFIGS: Lazy<Mutex<HashMap<PathBuf, Arc<Config>>>> = Lazy::new(Default::default);
+
+/// The config of the crate which is currently compiling.
+pub fn config() -> Arc<Config> { config_of(Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default())) }
+
+/// The config of the crate in `manifest_dir`, eg. for `cargo tia` which is not in the build of the crate.
+pub fn config_of(manifest_dir: &Path) -> Arc<Config>
+{
+ CONFIGS
+  .lock()
+  .unwrap_or_else(|e| e.into_inner())
+  .entry(manifest_dir.to_path_buf())
+  .or_insert_with(|| Arc::new(load(manifest_dir)))
+  .clone()
+}
+
+fn load(manifest_dir: &Path) -> Config
+{
+ let mut config = Config {
+  manifest_dir: manifest_dir.to_path_buf(),
+  ..Config::default()
+ };
+
+ let manifest_path = manifest_dir.join(CARGO_MANIFEST_FILE);
+ if let Some(manifest) = read_toml(&manifest_path)
+ {
+  config.edition = manifest
+   .get("package")
+   .and_then(|package| package.get("edition"))
+   .and_then(|edition| edition.as_str())
+   .map(|edition| edition.to_string());
+  if let Some(metadata) = manifest
+   .get("package")
+   .and_then(|package| package.get("metadata"))
+   .and_then(|metadata| metadata.get("tia"))
+  {
+   config.apply(metadata, &manifest_path);
+  }
+ }
+
+ let path = manifest_dir.join(CONFIG_FILE);
+ if let Some(value) = read_toml(&path)
+ {
+  config.apply(&value, &path);
+  config.path = Some(path);
+ }
+
+ config
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value>
+{
+ let source = std::fs::read_to_string(path).ok()?;
+ let value = source
+  .parse::<toml::Value>()
+  .unwrap_or_else(|e| {
+   panic!(
+    "tia config error: `{}` could not be parsed. ({}) #TIA-PANIC-4004",
+    path.display(),
+    e
+   )
+  });
+ Some(value)
+}
+
+impl Config
+{
+ /// Overwrite the settings with the keys of `value`, the presets are merged.
+ fn apply(&mut self, value: &toml::Value, path: &Path)
+ {
+  let get_str = |key: &str| {
+   value
+    .get(key)
+    .map(|v| {
+     v.as_str()
+      .unwrap_or_else(|| invalid(path, key, "a string"))
+    })
+  };
+  let get_bool = |key: &str| {
+   value
+    .get(key)
+    .map(|v| {
+     v.as_bool()
+      .unwrap_or_else(|| invalid(path, key, "a boolean"))
+    })
+  };
+
+  if let Some(presets) = value.get("presets")
+  {
+   let presets = presets
+    .as_table()
+    .unwrap_or_else(|| invalid(path, "presets", "a table"));
+   for (name, directives) in presets
+   {
+    let directives = directives
+     .as_str()
+     .unwrap_or_else(|| {
+      panic!(
+       r#"tia config error: `[presets] {}` of `{}` should be a string such as `"g, s, rsi"`. #TIA-PANIC-4005"#,
+       name,
+       path.display()
+      )
+     });
+    self
+     .presets
+     .insert(name.clone(), directives.to_string());
+   }
+  }
+  if let Some(naming) = get_str("naming")
+  {
+   self.naming = match naming
+   {
+    "get_set" => NamingStyle::GetSet,
+    "bare" => NamingStyle::Bare,
+    _ => invalid(path, "naming", r#""get_set" or "bare""#)
+   };
+  }
+  if let Some(visibility) = get_str("visibility")
+  {
+   if syn::parse_str::<syn::Visibility>(visibility).is_err()
+   {
+    invalid(path, "visibility", r#"a visibility such as "pub" or "pub(crate)""#)
+   }
+   self.visibility = visibility.to_string();
+  }
+  if let Some(inline) = get_str("inline")
+  {
+   self.inline = match inline
+   {
+    "never" => InlinePolicy::Never,
+    "inline" => InlinePolicy::Inline,
+    "always" => InlinePolicy::Always,
+    _ => invalid(path, "inline", r#""never", "inline" or "always""#)
+   };
+  }
+  if let Some(deterministic) = get_bool("deterministic")
+  {
+   self.deterministic = deterministic;
+  }
+  if let Some(print) = get_bool("print")
+  {
+   self.print = print;
+  }
+  if let Some(file) = get_bool("file")
+  {
+   self.file = file;
+  }
+  if let Some(rustfmt) = get_bool("rustfmt")
+  {
+   self.rustfmt = rustfmt;
+  }
+  if let Some(formatter) = get_str("formatter")
+  {
+   self.formatter = Some(match formatter
+   {
+    "prettyplease" => Formatter::Prettyplease,
+    "rustfmt" => Formatter::Rustfmt,
+    _ => invalid(path, "formatter", r#""prettyplease" or "rustfmt""#)
+   });
+  }
+  if let Some(out_dir) = get_str("out_dir")
+  {
+   self.out_dir = out_dir.to_string();
+  }
+ }
+}
+
+impl Config
+{
+ /// The formatter of the generated file, or `None` if the file is not prettified.
+ /// The file is prettified with the `file-pretty` or `include-pretty` feature, or `rustfmt = true`.
+ /// `formatter` is always used if it is written. If not, `rustfmt = true` uses `rustfmt` and the features use `prettyplease`.
+ pub fn pretty_formatter(&self) -> Option<Formatter>
+ {
+  let is_pretty = cfg!(feature = "file-pretty") || cfg!(feature = "include-pretty") || self.rustfmt;
+  match (is_pretty, self.formatter, self.rustfmt)
+  {
+   (false, ..) => None,
+   (true, Some(formatter), _) => Some(formatter),
+   (true, None, true) => Some(Formatter::Rustfmt),
+   (true, None, false) => Some(Formatter::Prettyplease)
+  }
+ }
+
+ /// The settings which change the generated code, eg. `naming`. The presets are sorted, thus it is same in every build.
+ pub fn fingerprint(&self) -> String
+ {
+  let mut presets = self
+   .presets
+   .iter()
+   .collect::<Vec<_>>();
+  presets.sort();
+  format!(
+   "{:?};{:?};{:?};{:?};{:?}",
+   presets, self.naming, self.visibility, self.inline, self.deterministic
+  )
+ }
+}
+
+fn invalid(path: &Path, key: &str, expected: &str) -> !
+{
+ panic!(
+  "tia config error: `{}` of `{}` should be {}. #TIA-PANIC-4006",
+  key,
+  path.display(),
+  expected
+ )
+}
+
+/// `const _: &[u8] = include_bytes!("/path/to/tia.toml");`, thus cargo will rebuild the crate if `tia.toml` is changed.
+/// It is for the token stream of the proc-macro only, the generated files should not contain the absolute path.
+pub fn generate_config_dependency(config: &Config) -> String
+{
+ config
+  .path
+  .as_ref()
+  .map(|path| format!("const _:&[u8]=include_bytes!({:?});", path.to_string_lossy()))
+  .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests
+{
+ use super::*;
+
+ fn apply(source: &str) -> Config
+ {
+  let mut config = Config::default();
+  config.apply(
+   &source
+    .parse::<toml::Value>()
+    .unwrap(),
+   Path::new(CONFIG_FILE)
+  );
+  config
+ }
+
+ fn temporary_dir(name: &str) -> PathBuf
+ {
+  let dir = std::env::temp_dir().join(format!("tia-config-{}-{}", std::process::id(), name));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(&dir).unwrap();
+  dir
+ }
+
+ #[test]
+ fn default()
+ {
+  let config = Config::default();
+  assert_eq!(config.naming, NamingStyle::GetSet);
+  assert_eq!(config.visibility, "pub");
+  assert_eq!(config.inline, InlinePolicy::Never);
+  assert!(config.deterministic);
+  assert!(!config.print && !config.file && !config.rustfmt);
+  assert_eq!(config.formatter, None);
+  assert_eq!(config.out_dir, DEFAULT_OUT_DIR);
+ }
+
+ #[test]
+ fn apply_all_keys()
+ {
+  let config = apply(
+   r#"
+naming = "bare"
+visibility = "pub(crate)"
+inline = "always"
+deterministic = false
+print = true
+file = true
+rustfmt = true
+formatter = "rustfmt"
+out_dir = "generated"
+
+[presets]
+pod = "g, s"
+"#
+  );
+  assert_eq!(config.naming, NamingStyle::Bare);
+  assert_eq!(config.visibility, "pub(crate)");
+  assert_eq!(config.inline, InlinePolicy::Always);
+  assert!(!config.deterministic);
+  assert!(config.print && config.file && config.rustfmt);
+  assert_eq!(config.formatter, Some(Formatter::Rustfmt));
+  assert_eq!(config.out_dir, "generated");
+  assert_eq!(config.presets["pod"], "g, s");
+ }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-4006")]
+ fn apply_invalid_value() { apply(r#"inline = "sometimes""#); }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-4006")]
+ fn apply_invalid_visibility() { apply(r#"visibility = "public""#); }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-4005")]
+ fn apply_invalid_preset() { apply("[presets]\npod = 1"); }
+
+ /// `tia.toml` overrides `[package.metadata.tia]` of `Cargo.toml` per key, and the presets are merged.
+ #[test]
+ fn load_precedence()
+ {
+  let dir = temporary_dir("precedence");
+  std::fs::write(
+   dir.join(CARGO_MANIFEST_FILE),
+   r#"
+[package]
+name = "sample"
+edition = "2018"
+
+[package.metadata.tia]
+naming = "bare"
+visibility = "pub(crate)"
+
+[package.metadata.tia.presets]
+pod = "g, s"
+readonly = "rg"
+"#
+  )
+  .unwrap();
+  std::fs::write(dir.join(CONFIG_FILE), "naming = \"get_set\"\n\n[presets]\nreadonly = \"rmg\"\n").unwrap();
+
+  let config = load(&dir);
+  assert_eq!(config.naming, NamingStyle::GetSet);
+  assert_eq!(config.visibility, "pub(crate)");
+  assert_eq!(config.presets["pod"], "g, s");
+  assert_eq!(config.presets["readonly"], "rmg");
+  assert_eq!(config.path, Some(dir.join(CONFIG_FILE)));
+  assert_eq!(config.manifest_dir, dir);
+  assert_eq!(
+   config
+    .edition
+    .as_deref(),
+   Some("2018")
+  );
+
+  std::fs::remove_file(dir.join(CONFIG_FILE)).unwrap();
+  let config = load(&dir);
+  assert_eq!(config.naming, NamingStyle::Bare);
+  assert_eq!(config.presets["readonly"], "rg");
+  assert_eq!(config.path, None);
+
+  std::fs::remove_dir_all(&dir).unwrap();
+ }
+
+ /// `tia.toml` of this repository is used by `tests/test.rs`.
+ #[test]
+ fn load_repository_config()
+ {
+  let config = load(Path::new(env!("CARGO_MANIFEST_DIR")));
+  assert_eq!(config.inline, InlinePolicy::Inline);
+  assert_eq!(config.presets["pod"], "g, s");
+  assert!(config
+   .path
+   .is_some());
+ }
+
+ /// `formatter` wins over `rustfmt = true`, and `rustfmt = true` uses `rustfmt` without `formatter`.
+ #[test]
+ fn pretty_formatter_precedence()
+ {
+  assert_eq!(apply("rustfmt = true").pretty_formatter(), Some(Formatter::Rustfmt));
+  assert_eq!(
+   apply("rustfmt = true\nformatter = \"prettyplease\"").pretty_formatter(),
+   Some(Formatter::Prettyplease)
+  );
+  match cfg!(feature = "file-pretty") || cfg!(feature = "include-pretty")
+  {
+   true =>
+   {
+    assert_eq!(Config::default().pretty_formatter(), Some(Formatter::Prettyplease));
+    assert_eq!(apply(r#"formatter = "rustfmt""#).pretty_formatter(), Some(Formatter::Rustfmt));
+   },
+   false =>
+   {
+    assert_eq!(Config::default().pretty_formatter(), None);
+    assert_eq!(apply(r#"formatter = "rustfmt""#).pretty_formatter(), None);
+   }
+  }
+ }
+
+ #[test]
+ fn fingerprint()
+ {
+  assert_eq!(
+   apply("[presets]\na = \"g\"\nb = \"s\"").fingerprint(),
+   apply("[presets]\nb = \"s\"\na = \"g\"").fingerprint()
+  );
+  assert_ne!(apply(r#"inline = "always""#).fingerprint(), Config::default().fingerprint());
+  // `print` does not change the generated code.
+  assert_eq!(apply("print = true").fingerprint(), Config::default().fingerprint());
+ }
+}
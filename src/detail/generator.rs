@@ -1,130 +1,364 @@
+mod delegate;
+mod dirty;
+mod field_enum;
+mod lens;
+mod merge;
+mod on_change;
+mod reflect;
+mod virtual_accessor;
+mod visitor;
+
 use super::*;
+pub use lens::generate_lens_types;
+pub use merge::generate_merge_invocation;
 use once_cell::sync::Lazy;
 
 static DEFAULT_GET_FIELD_SYMBOL_POLICY: Lazy<FieldSymbolPolicy> = Lazy::new(|| FieldSymbolPolicy::Prefix("get".to_string()));
 static DEFAULT_SET_FIELD_SYMBOL_POLICY: Lazy<FieldSymbolPolicy> = Lazy::new(|| FieldSymbolPolicy::Prefix("set".to_string()));
+static BARE_MOVE_FIELD_SYMBOL_POLICY: Lazy<FieldSymbolPolicy> = Lazy::new(|| FieldSymbolPolicy::Prefix("into".to_string()));
+static BARE_FIELD_SYMBOL_POLICY: Lazy<FieldSymbolPolicy> = Lazy::new(|| FieldSymbolPolicy::Bare);
+static BARE_REF_MUT_FIELD_SYMBOL_POLICY: Lazy<FieldSymbolPolicy> = Lazy::new(|| FieldSymbolPolicy::Suffix("mut".to_string()));
 
-pub fn generate_impl_definitions(ttfa: &TraitToFieldAccessors, impl_target_symbol: &String, target_type: TargetType) -> String
+pub fn generate_impl_definitions(tia: &Tia) -> String
 {
- ttfa
+ let ttfa = &tia.trait_to_field_accessors;
+
+ let field_enum = field_enum::generate_field_enum(tia);
+ let guards = on_change::generate_guards(tia);
+ let dirty = dirty::generate_dirty(tia);
+ let reflect = reflect::generate_reflect(tia);
+ let visitor = visitor::generate_visitor(tia);
+ let lenses = lens::generate_lenses(tia);
+ let merge_macros = merge::generate_merge_macros(tia);
+ let trait_declarations = sorted_entries(
+  &tia
+   .type_params
+   .trait_definitions,
+  tia
+ )
+ .into_iter()
+ .map(|(trait_symbol, trait_definition)| generate_trait_declaration(trait_symbol, trait_definition, ttfa.get(trait_symbol), tia));
+ let impl_definitions = sorted_entries(ttfa, tia)
+  .into_iter()
+  .filter(|(trait_symbol, _)| {
+   !tia
+    .type_params
+    .merged_traits
+    .contains(*trait_symbol)
+  })
+  .map(|(trait_symbol, field_to_accessors)| generate_impl_definition(trait_symbol, field_to_accessors, tia));
+
+ let delegations = tia
+  .target_fields
   .iter()
-  .map(|(trait_symbol, field_to_accessors)| generate_impl_definition(trait_symbol, field_to_accessors, &impl_target_symbol, &target_type))
+  .map(|target_field| delegate::generate_delegations(target_field, &tia.target_type_symbol, &tia.target_type));
+
+ [field_enum, guards, dirty, reflect, visitor, lenses, merge_macros]
+  .into_iter()
+  .chain(trait_declarations)
+  .chain(impl_definitions)
+  .chain(delegations)
   .collect::<Vec<String>>()
   .join(NO_SEPARATOR)
 }
 
-fn generate_impl_definition(
+fn generate_trait_declaration(
  trait_symbol: &TraitSymbol,
- field_to_accessors: &FieldSymbolToFieldParams,
- impl_target_symbol: &String,
- target_type: &TargetType
+ trait_definition: &TraitDefinition,
+ field_to_accessors: Option<&FieldSymbolToFieldParams>,
+ tia: &Tia
 ) -> String
+{
+ let body = field_to_accessors
+  .map(|field_to_accessors| {
+   sorted_fields(field_to_accessors, tia)
+    .into_iter()
+    .flat_map(|(field_symbol, field_params)| {
+     let signatures = field_params
+      .accessors
+      .iter()
+      .map(move |accessor| {
+       let (signature, _) = generate_field_accessor(field_symbol, field_params, accessor, tia);
+       format!("{};", signature)
+      })
+      .collect::<Vec<String>>();
+     sorted(signatures, tia)
+    })
+    .collect::<Vec<String>>()
+    .join(NO_SEPARATOR)
+  })
+  .unwrap_or_default();
+ let virtual_body = virtual_accessor::generate_virtual_accessors(trait_symbol, tia)
+  .into_iter()
+  .map(|(signature, _)| format!("{};", signature))
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+
+ format!("{}{{{}{}}}", trait_definition.declaration_header, body, virtual_body)
+}
+
+fn generate_impl_definition(trait_symbol: &TraitSymbol, field_to_accessors: &FieldSymbolToFieldParams, tia: &Tia) -> String
 {
  let is_pub = trait_symbol.is_empty();
- let header = generate_impl_header(trait_symbol, impl_target_symbol);
- let body = generate_impl_body(field_to_accessors, is_pub, target_type);
+ let header = generate_impl_header(trait_symbol, &tia.target_type_symbol, &tia.type_params);
+ let body = generate_impl_body(trait_symbol, field_to_accessors, is_pub, tia);
  let footer = generate_impl_footer();
 
- vec![header, body, footer]
-  .join(NO_SEPARATOR)
-  .into()
+ [header, body, footer].join(NO_SEPARATOR)
 }
 
-fn generate_impl_header(trait_symbol: &TraitSymbol, impl_target_symbol: &String) -> String
+fn generate_impl_header(trait_symbol: &TraitSymbol, impl_target_symbol: &String, type_params: &TypeParams) -> String
 {
- let trait_part = match trait_symbol.is_empty()
+ match (
+  trait_symbol.is_empty(),
+  type_params
+   .trait_definitions
+   .get(trait_symbol)
+ )
  {
-  true => EMPTY_STR.into(),
-  false => format!("{} for ", trait_symbol)
- };
- format!("impl {}{}{{", trait_part, impl_target_symbol)
+  (true, _) => format!("impl {}{{", impl_target_symbol),
+  (false, None) => format!("impl {} for {}{{", trait_symbol, impl_target_symbol),
+  (false, Some(trait_definition)) =>
+  {
+   format!(
+    "impl{} {} for {} {}{{",
+    trait_definition.impl_generics, trait_symbol, impl_target_symbol, trait_definition.where_clause
+   )
+  }
+ }
 }
 
-fn generate_impl_body(field_to_accessors: &FieldSymbolToFieldParams, is_pub: bool, target_type: &TargetType) -> String
+fn generate_impl_body(trait_symbol: &TraitSymbol, field_to_accessors: &FieldSymbolToFieldParams, is_pub: bool, tia: &Tia) -> String
 {
- field_to_accessors
-  .iter()
-  .map(|(field_symbol, field_params)| generate_field_accessors(field_symbol, field_params, is_pub, target_type))
+ let prefix = generate_accessor_prefix(is_pub, tia);
+ let virtual_accessors = virtual_accessor::generate_virtual_accessors(trait_symbol, tia)
+  .into_iter()
+  .map(|(signature, body)| format!("{}{}{{{}}}", prefix, signature, body));
+
+ sorted_fields(field_to_accessors, tia)
+  .into_iter()
+  .map(|(field_symbol, field_params)| generate_field_accessors(field_symbol, field_params, is_pub, tia))
+  .chain(virtual_accessors)
   .collect::<Vec<String>>()
   .join(NO_SEPARATOR)
 }
 
 fn generate_impl_footer() -> String { "}".into() }
 
-fn generate_field_accessors(field_symbol: &FieldSymbol, field_params: &FieldParams, is_pub: bool, target_type: &TargetType) -> String
+fn generate_field_accessors(field_symbol: &FieldSymbol, field_params: &FieldParams, is_pub: bool, tia: &Tia) -> String
 {
- let FieldParams {
-  field_type,
-  accessors
- } = field_params;
+ let prefix = generate_accessor_prefix(is_pub, tia);
 
- accessors
+ let accessors = field_params
+  .accessors
   .iter()
-  .map(|accessor| generate_field_accessor(field_symbol, field_type, accessor, is_pub, target_type))
-  .collect::<Vec<String>>()
-  .join(NO_SEPARATOR)
+  .map(|accessor| {
+   let (signature, body) = generate_field_accessor(field_symbol, field_params, accessor, tia);
+   format!("{}{}{{{}}}", prefix, signature, body)
+  })
+  .collect::<Vec<String>>();
+ sorted(accessors, tia).join(NO_SEPARATOR)
 }
 
-fn generate_field_accessor(
- field_symbol: &FieldSymbol,
- field_type: &FieldType,
- accessor: &Accessor,
- is_pub: bool,
- target_type: &TargetType
-) -> String
+/// `#[inline]` and `pub ` of the accessors; the visibility of the inherent impl could be set by `visibility="..."` of the config.
+fn generate_accessor_prefix(is_pub: bool, tia: &Tia) -> String
 {
- let fn_definition = match accessor
+ let visibility = match is_pub
+ {
+  true => generate_inherent_visibility(tia),
+  false => String::new()
+ };
+ format!(
+  "{}{}",
+  tia
+   .config
+   .inline
+   .attribute(),
+  visibility
+ )
+}
+
+/// `pub ` of the inherent items such as the accessors and `visit_fields`, or `visibility="..."` of the config.
+fn generate_inherent_visibility(tia: &Tia) -> String
+{
+ format!(
+  "{} ",
+  tia
+   .config
+   .visibility
+ )
+}
+
+/// The entries of the map sorted by the key, or as is if `deterministic = false` of the config.
+fn sorted_entries<'a, K: Ord + 'a, V: 'a>(map: impl IntoIterator<Item = (&'a K, &'a V)>, tia: &Tia) -> Vec<(&'a K, &'a V)>
+{
+ let mut entries = map
+  .into_iter()
+  .collect::<Vec<_>>();
+ if tia
+  .config
+  .deterministic
+ {
+  entries.sort_by(|a, b| {
+   a.0
+    .cmp(b.0)
+  });
+ }
+ entries
+}
+
+/// The fields in the declaration order, the flattened members follow them; or as is if `deterministic = false` of the config.
+fn sorted_fields<'a>(field_to_accessors: &'a FieldSymbolToFieldParams, tia: &Tia) -> Vec<(&'a FieldSymbol, &'a FieldParams)>
+{
+ let mut fields = field_to_accessors
+  .iter()
+  .collect::<Vec<_>>();
+ if tia
+  .config
+  .deterministic
+ {
+  fields.sort_by_key(|(field_symbol, _)| {
+   let position = tia
+    .target_fields
+    .iter()
+    .position(|target_field| &target_field.field_symbol == *field_symbol)
+    .unwrap_or(usize::MAX);
+   (position, *field_symbol)
+  });
+ }
+ fields
+}
+
+fn sorted(mut items: Vec<String>, tia: &Tia) -> Vec<String>
+{
+ if tia
+  .config
+  .deterministic
+ {
+  items.sort();
+ }
+ items
+}
+
+/// Returns ( signature, body ) of the accessor fn.
+fn generate_field_accessor(field_symbol: &FieldSymbol, field_params: &FieldParams, accessor: &Accessor, tia: &Tia) -> (String, String)
+{
+ let unsafe_token = match tia.target_type
+ {
+  TargetType::Union => "unsafe ",
+  _ => ""
+ };
+ if matches!(tia.target_type, TargetType::Union)
+  && field_params
+   .path
+   .is_some()
+ {
+  panic!("tia not implemented feature: `flatten` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2010")
+ }
+ let access = generate_field_access(field_symbol, field_params);
+ match accessor
  {
   Accessor::Getter {
    fsp,
    ptp
   } =>
   {
-   let unsafe_token = match target_type
-   {
-    TargetType::Union => "unsafe ",
-    _ => ""
-   };
-   format!("{}{}", unsafe_token, generate_get_accessor(field_symbol, field_type, fsp, ptp))
+   let (signature, body) = generate_get_accessor(field_symbol, field_params, fsp, ptp, tia);
+   (format!("{}{}", unsafe_token, signature), body)
   },
   Accessor::Setter {
    fsp,
-   ptp
-  } => generate_set_accessor(field_symbol, field_type, fsp, ptp)
- };
-
- let pub_token = match is_pub
- {
-  true => "pub ",
-  false => ""
- };
-
- format!("{}{}", pub_token, fn_definition)
+   ptp,
+   svp
+  } => generate_set_accessor(field_symbol, field_params, fsp, ptp, svp.as_ref(), tia),
+  Accessor::Delegate {
+   fn_symbol,
+   signature,
+   arguments
+  } =>
+  {
+   (
+    format!("{}{}", unsafe_token, signature),
+    format!("self.{}.{}({})", access, fn_symbol, arguments)
+   )
+  },
+ }
 }
 
 fn generate_get_accessor(
  field_symbol: &FieldSymbol,
- field_type: &FieldType,
+ field_params: &FieldParams,
  fsp: &FieldSymbolPolicy,
- gptp: &GetterParameterTypePolicy
-) -> String
+ gptp: &GetterParameterTypePolicy,
+ tia: &Tia
+) -> (String, String)
 {
- let fsp = match fsp
+ let field_type = &field_params.field_type;
+ let access = generate_field_access(field_symbol, field_params);
+ let fsp = match (
+  fsp,
+  tia
+   .type_params
+   .naming_style,
+  gptp
+ )
  {
-  FieldSymbolPolicy::Default => &DEFAULT_GET_FIELD_SYMBOL_POLICY,
+  (FieldSymbolPolicy::Default, NamingStyle::GetSet, _) => &DEFAULT_GET_FIELD_SYMBOL_POLICY,
+  (FieldSymbolPolicy::Default, NamingStyle::Bare, GetterParameterTypePolicy::Move) => &BARE_MOVE_FIELD_SYMBOL_POLICY,
+  (FieldSymbolPolicy::Default, NamingStyle::Bare, GetterParameterTypePolicy::RefMut) => &BARE_REF_MUT_FIELD_SYMBOL_POLICY,
+  (FieldSymbolPolicy::Default, NamingStyle::Bare, _) => &BARE_FIELD_SYMBOL_POLICY,
   _ => fsp
  };
  let function_symbol = generate_function_symbol(field_symbol, fsp);
  match gptp
  {
-  GetterParameterTypePolicy::Move => format!("fn {}(self)->{}{{self.{}}}", function_symbol, field_type, field_symbol),
-  GetterParameterTypePolicy::Value => format!("fn {}(&self)->{}{{self.{}}}", function_symbol, field_type, field_symbol),
-  GetterParameterTypePolicy::Ref => format!("fn {}(&self)->&{}{{&self.{}}}", function_symbol, field_type, field_symbol),
+  GetterParameterTypePolicy::Move => (format!("fn {}(self)->{}", function_symbol, field_type), format!("self.{}", access)),
+  GetterParameterTypePolicy::Value => (format!("fn {}(&self)->{}", function_symbol, field_type), format!("self.{}", access)),
+  GetterParameterTypePolicy::Ref =>
+  {
+   (
+    format!("fn {}(&self)->&{}", function_symbol, field_type),
+    format!("&self.{}", access)
+   )
+  },
+  GetterParameterTypePolicy::Clone =>
+  {
+   (
+    format!("fn {}(&self)->{}", function_symbol, field_type),
+    format!("::core::clone::Clone::clone(&self.{})", access)
+   )
+  },
+  GetterParameterTypePolicy::Into(into_type) if into_type.is_empty() =>
+  {
+   panic!(r#"tia syntax error: `gi` requires the target type such as `gi(ty=u64)`. #TIA-PANIC-2012"#)
+  },
+  GetterParameterTypePolicy::Into(into_type) =>
+  {
+   (
+    format!("fn {}(&self)->{}", function_symbol, into_type),
+    format!("::core::convert::Into::into(::core::clone::Clone::clone(&self.{}))", access)
+   )
+  },
   GetterParameterTypePolicy::RefMut =>
   {
-   format!(
-    "fn {}(&mut self)->&mut {}{{&mut self.{}}}",
-    function_symbol, field_type, field_symbol
+   let (signature, body) = match field_params.on_change
+   {
+    Some(_) => on_change::generate_guard_accessor(&function_symbol, field_symbol, field_params, tia),
+    None =>
+    {
+     (
+      format!("fn {}(&mut self)->&mut {}", function_symbol, field_type),
+      format!("&mut self.{}", access)
+     )
+    },
+   };
+   (
+    signature,
+    format!(
+     "{}{}",
+     dirty::generate_mark(&owner_field_symbol(field_symbol, field_params), tia),
+     body
+    )
    )
   }
  }
@@ -132,37 +366,209 @@ fn generate_get_accessor(
 
 fn generate_set_accessor(
  field_symbol: &FieldSymbol,
- field_type: &FieldType,
+ field_params: &FieldParams,
  fsp: &FieldSymbolPolicy,
- sptp: &SetterParameterTypePolicy
-) -> String
+ sptp: &SetterParameterTypePolicy,
+ svp: Option<&SetterValidationPolicy>,
+ tia: &Tia
+) -> (String, String)
 {
- let fsp = match fsp
+ let field_type = &field_params.field_type;
+ let access = generate_field_access(field_symbol, field_params);
+ let function_symbol = match (fsp, sptp)
  {
-  FieldSymbolPolicy::Default => &DEFAULT_SET_FIELD_SYMBOL_POLICY,
-  _ => fsp
+  // `rsp` => `set_foo_str`
+  (FieldSymbolPolicy::Default, SetterParameterTypePolicy::Parse) => format!("set_{}_str", field_symbol),
+  (FieldSymbolPolicy::Default, _) => generate_function_symbol(field_symbol, &DEFAULT_SET_FIELD_SYMBOL_POLICY),
+  _ => generate_function_symbol(field_symbol, fsp)
  };
- let function_symbol = generate_function_symbol(field_symbol, fsp);
- match sptp
+ // ( parameters, prologue, the new value expression, the plain assignment )
+ let (parameters, prologue, new_value, assignment) = match (sptp, svp)
  {
-  SetterParameterTypePolicy::Value => format!("fn {}(&mut self,v:{}){{self.{}=v;}}", function_symbol, field_type, field_symbol),
-  SetterParameterTypePolicy::RefClone =>
+  (SetterParameterTypePolicy::Value, _) =>
   {
-   format!(
-    "fn {}(&mut self,v:&{}){{self.{}.clone_from(v);}}",
-    function_symbol, field_type, field_symbol
+   (
+    format!("(&mut self,v:{})", field_type),
+    String::new(),
+    "v".to_string(),
+    format!("self.{}=v;", access)
    )
   },
-  SetterParameterTypePolicy::Into =>
+  (SetterParameterTypePolicy::RefClone, _) =>
   {
-   format!(
-    "fn {}<T:Into<{}>>(&mut self,v:T){{self.{}=v.into();}}",
-    function_symbol, field_type, field_symbol
+   (
+    format!("(&mut self,v:&{})", field_type),
+    String::new(),
+    "::core::clone::Clone::clone(v)".to_string(),
+    format!("self.{}.clone_from(v);", access)
    )
-  }
+  },
+  (SetterParameterTypePolicy::Into, None) =>
+  {
+   (
+    format!("<T:Into<{}>>(&mut self,v:T)", field_type),
+    String::new(),
+    "v.into()".to_string(),
+    format!("self.{}=v.into();", access)
+   )
+  },
+  (SetterParameterTypePolicy::Into, Some(_)) =>
+  {
+   (
+    format!("<T:Into<{}>>(&mut self,v:T)", field_type),
+    format!("let v:{}=v.into();", field_type),
+    "v".to_string(),
+    format!("self.{}=v;", access)
+   )
+  },
+  (SetterParameterTypePolicy::TryInto, _) =>
+  {
+   (
+    format!("<T: ::core::convert::TryInto<{}>>(&mut self,v:T)", field_type),
+    format!("let v:{}=::core::convert::TryInto::try_into(v)?;", field_type),
+    "v".to_string(),
+    format!("self.{}=v;", access)
+   )
+  },
+  (SetterParameterTypePolicy::AsRef(borrowed_type), _) =>
+  {
+   let borrowed_type = generate_borrowed_type(field_type, borrowed_type);
+   (
+    format!("<T:AsRef<{}>>(&mut self,v:T)", borrowed_type),
+    format!(
+     "let v:{}=::std::borrow::ToOwned::to_owned(AsRef::<{}>::as_ref(&v));",
+     field_type, borrowed_type
+    ),
+    "v".to_string(),
+    format!("self.{}=v;", access)
+   )
+  },
+  (SetterParameterTypePolicy::Parse, _) =>
+  {
+   (
+    "(&mut self,v:&str)".to_string(),
+    format!("let v:{}=::core::str::FromStr::from_str(v)?;", field_type),
+    "v".to_string(),
+    format!("self.{}=v;", access)
+   )
+  },
+ };
+
+ let validation = svp
+  .map(|svp| generate_setter_validation(field_symbol, svp, *sptp == SetterParameterTypePolicy::RefClone))
+  .unwrap_or_default();
+ let assignment = match &field_params.on_change
+ {
+  Some(on_change) => on_change::generate_hooked_assignment(field_symbol, field_params, &new_value, on_change, tia),
+  None => assignment
+ };
+ let mark = dirty::generate_mark(&owner_field_symbol(field_symbol, field_params), tia);
+ let body = [prologue, validation, assignment, mark].join(NO_SEPARATOR);
+
+ match generate_setter_error_type(field_type, sptp, svp)
+ {
+  None => (format!("fn {}{}", function_symbol, parameters), body),
+  Some(error_type) =>
+  {
+   (
+    format!("fn {}{}->::core::result::Result<(),{}>", function_symbol, parameters, error_type),
+    format!("{}::core::result::Result::Ok(())", body)
+   )
+  },
  }
 }
 
+/// `E` of `Result<(), E>` if the setter is fallible.
+fn generate_setter_error_type(
+ field_type: &FieldType,
+ sptp: &SetterParameterTypePolicy,
+ svp: Option<&SetterValidationPolicy>
+) -> Option<String>
+{
+ match (sptp, svp)
+ {
+  (SetterParameterTypePolicy::TryInto, _) => Some(format!("<T as ::core::convert::TryInto<{}>>::Error", field_type)),
+  (SetterParameterTypePolicy::Parse, _) => Some(format!("<{} as ::core::str::FromStr>::Err", field_type)),
+  (_, Some(svp)) =>
+  {
+   Some(
+    svp
+     .error
+     .clone()
+     .unwrap_or_else(|| "String".to_string())
+   )
+  },
+  (_, None) => None
+ }
+}
+
+/// `T` of `AsRef<T>` for `rsa`; `PathBuf` => `<PathBuf as Deref>::Target` (`Path`) if `ty` is not set.
+fn generate_borrowed_type(field_type: &FieldType, borrowed_type: &Option<FieldType>) -> String
+{
+ borrowed_type
+  .clone()
+  .unwrap_or_else(|| format!("<{} as ::core::ops::Deref>::Target", field_type))
+}
+
+/// The checks of `v` before the assignment; `is_ref` is true if `v` is `&T`, false if `v` is `T`.
+fn generate_setter_validation(field_symbol: &FieldSymbol, svp: &SetterValidationPolicy, is_ref: bool) -> String
+{
+ let (deref, r#ref) = match is_ref
+ {
+  true => ("*", ""),
+  false => ("", "&")
+ };
+ let fail = |message: String| format!("return ::core::result::Result::Err(::core::convert::From::from({:?}));", message);
+
+ let min = svp
+  .min
+  .as_ref()
+  .map(|min| {
+   format!(
+    "if {}v<{}{{{}}}",
+    deref,
+    min,
+    fail(format!("{} is less than the min {}", field_symbol, min))
+   )
+  });
+ let max = svp
+  .max
+  .as_ref()
+  .map(|max| {
+   format!(
+    "if {}v>{}{{{}}}",
+    deref,
+    max,
+    fail(format!("{} is greater than the max {}", field_symbol, max))
+   )
+  });
+ let len = svp
+  .len
+  .as_ref()
+  .map(|len| {
+   let condition = match len.contains("..")
+   {
+    true => format!("!({}).contains(&v.len())", len),
+    false => format!("v.len()!={}", len)
+   };
+   format!(
+    "if {}{{{}}}",
+    condition,
+    fail(format!("{}.len() is out of the range {}", field_symbol, len))
+   )
+  });
+ let validate = svp
+  .validate
+  .as_ref()
+  .map(|validate| format!("{}({}v)?;", validate, r#ref));
+
+ [min, max, len, validate]
+  .into_iter()
+  .flatten()
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR)
+}
+
 fn generate_function_symbol(field_symbol: &FieldSymbol, field_symbol_policy: &FieldSymbolPolicy) -> String
 {
  match field_symbol_policy
@@ -170,6 +576,7 @@ fn generate_function_symbol(field_symbol: &FieldSymbol, field_symbol_policy: &Fi
   FieldSymbolPolicy::Prefix(prefix) => format!("{}_{}", prefix, field_symbol),
   FieldSymbolPolicy::Suffix(suffix) => format!("{}_{}", field_symbol, suffix),
   FieldSymbolPolicy::Fullname(fullname) => fullname.clone(),
+  FieldSymbolPolicy::Bare => field_symbol.clone(),
   _ =>
   {
    panic!(
@@ -178,3 +585,253 @@ fn generate_function_symbol(field_symbol: &FieldSymbol, field_symbol_policy: &Fi
   }
  }
 }
+
+/// The `FieldParams` of the field for each trait.
+fn find_field_params<'a>(field_symbol: &'a FieldSymbol, tia: &'a Tia) -> impl Iterator<Item = &'a FieldParams> + 'a
+{
+ tia
+  .trait_to_field_accessors
+  .values()
+  .filter_map(move |field_to_accessors| field_to_accessors.get(field_symbol))
+}
+
+/// true if some trait has an accessor of the field which satisfies `predicate`.
+fn has_accessor(field_symbol: &FieldSymbol, tia: &Tia, predicate: impl Fn(&Accessor) -> bool) -> bool
+{
+ find_field_params(field_symbol, tia).any(|field_params| {
+  field_params
+   .accessors
+   .iter()
+   .any(&predicate)
+ })
+}
+
+fn is_ref_mut_getter(accessor: &Accessor) -> bool
+{
+ matches!(accessor, Accessor::Getter {
+  ptp: GetterParameterTypePolicy::RefMut,
+  ..
+ })
+}
+
+/// The place of the accessor target after `self.`: `foo`, or `pos.x` for a flattened accessor.
+fn generate_field_access(field_symbol: &FieldSymbol, field_params: &FieldParams) -> String
+{
+ field_params
+  .path
+  .clone()
+  .unwrap_or_else(|| field_symbol.clone())
+}
+
+/// The field which contains the accessor target: `foo`, or `pos` for `pos.x`.
+fn owner_field_symbol(field_symbol: &FieldSymbol, field_params: &FieldParams) -> FieldSymbol
+{
+ match &field_params.path
+ {
+  Some(path) =>
+  {
+   path
+    .split('.')
+    .next()
+    .unwrap_or_default()
+    .to_string()
+  },
+  None => field_symbol.clone()
+ }
+}
+
+#[cfg(test)]
+mod tests
+{
+ use super::*;
+
+ fn generate(source: &str, config: config::Config) -> String
+ {
+  let i = syn::parse_str::<syn::DeriveInput>(source).unwrap();
+  let tia = parser::parse_with(i, std::sync::Arc::new(config)).unwrap();
+  generate_impl_definitions(&tia)
+ }
+
+ const SAMPLE: &str = "#[tia(rg, s)] struct Sample { b: i32, a: i32 }";
+
+ #[test]
+ fn config_naming()
+ {
+  let generated = generate(SAMPLE, config::Config {
+   naming: NamingStyle::Bare,
+   ..Default::default()
+  });
+  assert!(generated.contains("fn a(&self)"));
+  assert!(!generated.contains("fn get_a"));
+ }
+
+ #[test]
+ fn config_visibility_and_inline()
+ {
+  let generated = generate(SAMPLE, config::Config::default());
+  assert!(generated.contains("pub fn get_a"));
+  assert!(!generated.contains("#[inline"));
+
+  let generated = generate(SAMPLE, config::Config {
+   visibility: "pub(crate)".to_string(),
+   inline: config::InlinePolicy::Always,
+   ..Default::default()
+  });
+  assert!(generated.contains("#[inline(always)]pub(crate) fn get_a"));
+ }
+
+ /// The helpers of the inherent impl such as `visit_fields` follow `visibility` too.
+ #[test]
+ fn config_visibility_of_helpers()
+ {
+  let generated = generate(
+   "#[tia(rg, rmg, dirty=\"d\", reflect, visitor)] struct Sample { a: i32, d: SampleDirty }",
+   config::Config {
+    visibility: "pub(crate)".to_string(),
+    ..Default::default()
+   }
+  );
+  for helper in [
+   "const FIELDS",
+   "fn field_names",
+   "fn get_by_name",
+   "fn set_by_name",
+   "fn is_dirty_a",
+   "fn dirty_fields",
+   "fn clear_dirty",
+   "fn visit_fields",
+   "fn visit_fields_mut"
+  ]
+  {
+   assert!(generated.contains(&format!("pub(crate) {}", helper)), "{}: {}", helper, generated);
+   assert!(!generated.contains(&format!("pub {}", helper)), "{}: {}", helper, generated);
+  }
+ }
+
+ /// The accessors follow the order of the fields, `b` then `a`.
+ #[test]
+ fn config_deterministic()
+ {
+  let generated = generate(SAMPLE, config::Config::default());
+  assert!(generated.find("fn get_b") < generated.find("fn get_a"));
+  assert_eq!(generated, generate(SAMPLE, config::Config::default()));
+ }
+
+ #[test]
+ fn field_enum_compact()
+ {
+  let generated = generate("#[tia(reflect)] struct Sample { a: i32 }", config::Config::default());
+  assert!(generated.contains("pub fn name(self)->&'static str"));
+  assert!(generated.contains("pub fn from_name(name:&str)"));
+  assert!(!generated.contains("  "));
+ }
+
+ #[test]
+ fn accessor_symbol_keyword()
+ {
+  let i = syn::parse_str::<syn::DeriveInput>(r#"struct Sample { #[tia(g *= "as")] a: i32 }"#).unwrap();
+  let e = parser::parse_with(i, std::sync::Arc::new(config::Config::default())).unwrap_err();
+  assert!(e
+   .to_string()
+   .contains("#TIA-ERROR-1065"));
+  // A prefix is followed by the field symbol, thus `as_a` is available.
+  assert!(generate(
+   r#"#[tia(gi(ty = u64, prefix = "as"))] struct Sample { a: u32 }"#,
+   config::Config::default()
+  )
+  .contains("fn as_a"));
+ }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-1066")]
+ fn accessor_symbol_keyword_in_arguments()
+ {
+  generate(
+   r#"#[tia(gi(ty = u64, fullname = "as"))] struct Sample { a: u32 }"#,
+   config::Config::default()
+  );
+ }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-1067")]
+ fn into_type_keyword() { generate(r#"#[tia(gi(ty = "as"))] struct Sample { a: u32 }"#, config::Config::default()); }
+
+ #[test]
+ fn delegate_paths()
+ {
+  let generated = generate(
+   r#"struct Sample { #[tia(delegate = "std::fmt::Display, fmt::Debug, ::core::cmp::PartialEq, Hash")] a: i32 }"#,
+   config::Config::default()
+  );
+  for trait_path in [
+   "::core::fmt::Display",
+   "::core::fmt::Debug",
+   "::core::cmp::PartialEq",
+   "::core::hash::Hash"
+  ]
+  {
+   assert!(generated.contains(&format!("impl {} for Sample", trait_path)), "{}", trait_path);
+  }
+ }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-1068")]
+ fn delegate_other_path()
+ {
+  generate(
+   r#"struct Sample { #[tia(delegate = "my::fmt::Display")] a: i32 }"#,
+   config::Config::default()
+  );
+ }
+
+ #[test]
+ fn merge_macro()
+ {
+  let generated = generate(
+   r#"#[tia("crate::api::Getter<i32>", rg, merge, "", merge)] struct Sample { a: i32 }"#,
+   config::Config::default()
+  );
+  // The arms per merged trait keyed by the path suffixes, and the fallback arm.
+  assert!(generated.contains("macro_rules! __tia_merge_Sample{(Getter_i32 $found:literal"));
+  assert!(generated.contains("(api__Getter_i32 $found:literal"));
+  assert!(generated.contains("(__tia_inherent $found:literal"));
+  assert!(generated.contains("#TIA-ERROR-2014"));
+  assert!(generated.contains("use __tia_merge_Sample;"));
+  // The merged `impl` is in the arms only.
+  assert_eq!(
+   generated
+    .matches("impl crate :: api :: Getter < i32 > for Sample")
+    .count(),
+   2
+  );
+
+  let item_impl = syn::parse_str::<syn::ItemImpl>("#[doc = \"x\"] impl api::Getter<i32> for Sample { fn f(&self) {} }").unwrap();
+  assert_eq!(
+   merge::generate_merge_invocation("Sample", &"api :: Getter < i32 >".to_string(), &item_impl).to_string(),
+   r#"__tia_merge_Sample ! { api__Getter_i32 "impl api::Getter<i32> for Sample" # [doc = "x"] ; fn f (& self) { } }"#
+  );
+ }
+
+ #[test]
+ fn merge_ambiguous_last_segment()
+ {
+  let generated = generate(
+   r#"#[tia("a::Getter", g, merge, "b::Getter", g, merge)] struct Sample { a: i32 }"#,
+   config::Config::default()
+  );
+  assert!(generated.contains("(a__Getter $found:literal"));
+  assert!(generated.contains("(b__Getter $found:literal"));
+  // `impl Getter for Sample` is an error.
+  assert!(generated.contains("(Getter $found:literal $($rest:tt)*)=>{compile_error!"));
+ }
+
+ #[test]
+ #[should_panic(expected = "#TIA-PANIC-2013")]
+ fn merge_same_path()
+ {
+  generate(
+   r#"#[tia("Getter", g, merge, "crate::Getter", g, merge)] struct Sample { a: i32 }"#,
+   config::Config::default()
+  );
+ }
+}
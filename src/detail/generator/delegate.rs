@@ -0,0 +1,154 @@
+use super::*;
+
+const FMT_TRAITS: [&str; 9] = [
+ "Display", "Debug", "Binary", "Octal", "LowerHex", "UpperHex", "LowerExp", "UpperExp", "Pointer"
+];
+
+pub fn generate_delegations(target_field: &TargetField, impl_target_symbol: &String, target_type: &TargetType) -> String
+{
+ if matches!(target_type, TargetType::Union)
+  && !target_field
+   .delegations
+   .is_empty()
+ {
+  panic!("tia not implemented feature: `delegate` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2003")
+ }
+
+ target_field
+  .delegations
+  .iter()
+  .map(|delegation| generate_delegation(delegation, &target_field.field_symbol, &target_field.field_type, impl_target_symbol))
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR)
+}
+
+fn generate_delegation(delegation: &str, field_symbol: &FieldSymbol, field_type: &FieldType, impl_target_symbol: &String) -> String
+{
+ let (trait_path, body) = match delegation
+ {
+  fmt if FMT_TRAITS.contains(&fmt) =>
+  {
+   (
+    format!("::core::fmt::{}", fmt),
+    format!(
+     "fn fmt(&self,f:&mut ::core::fmt::Formatter<'_>)->::core::fmt::Result{{::core::fmt::{}::fmt(&self.{},f)}}",
+     fmt, field_symbol
+    )
+   )
+  },
+  "Iterator" =>
+  {
+   (
+    "::core::iter::Iterator".to_string(),
+    format!(
+     "type Item=<{ft} as ::core::iter::Iterator>::Item;fn next(&mut \
+      self)->::core::option::Option<Self::Item>{{::core::iter::Iterator::next(&mut self.{fs})}}fn \
+      size_hint(&self)->(usize,::core::option::Option<usize>){{::core::iter::Iterator::size_hint(&self.{fs})}}",
+     ft = field_type,
+     fs = field_symbol
+    )
+   )
+  },
+  "DoubleEndedIterator" =>
+  {
+   (
+    "::core::iter::DoubleEndedIterator".to_string(),
+    format!(
+     "fn next_back(&mut self)->::core::option::Option<Self::Item>{{::core::iter::DoubleEndedIterator::next_back(&mut self.{})}}",
+     field_symbol
+    )
+   )
+  },
+  "ExactSizeIterator" =>
+  {
+   (
+    "::core::iter::ExactSizeIterator".to_string(),
+    format!(
+     "fn len(&self)->usize{{::core::iter::ExactSizeIterator::len(&self.{})}}",
+     field_symbol
+    )
+   )
+  },
+  "Deref" =>
+  {
+   (
+    "::core::ops::Deref".to_string(),
+    format!(
+     "type Target={};fn deref(&self)->&Self::Target{{&self.{}}}",
+     field_type, field_symbol
+    )
+   )
+  },
+  "DerefMut" =>
+  {
+   (
+    "::core::ops::DerefMut".to_string(),
+    format!("fn deref_mut(&mut self)->&mut Self::Target{{&mut self.{}}}", field_symbol)
+   )
+  },
+  "AsRef" =>
+  {
+   (
+    format!("::core::convert::AsRef<{}>", field_type),
+    format!("fn as_ref(&self)->&{}{{&self.{}}}", field_type, field_symbol)
+   )
+  },
+  "AsMut" =>
+  {
+   (
+    format!("::core::convert::AsMut<{}>", field_type),
+    format!("fn as_mut(&mut self)->&mut {}{{&mut self.{}}}", field_type, field_symbol)
+   )
+  },
+  "Hash" =>
+  {
+   (
+    "::core::hash::Hash".to_string(),
+    format!(
+     "fn hash<H:::core::hash::Hasher>(&self,state:&mut H){{::core::hash::Hash::hash(&self.{},state)}}",
+     field_symbol
+    )
+   )
+  },
+  "PartialEq" =>
+  {
+   (
+    "::core::cmp::PartialEq".to_string(),
+    format!("fn eq(&self,other:&Self)->bool{{self.{fs}==other.{fs}}}", fs = field_symbol)
+   )
+  },
+  "Eq" => ("::core::cmp::Eq".to_string(), String::default()),
+  "PartialOrd" =>
+  {
+   (
+    "::core::cmp::PartialOrd".to_string(),
+    format!(
+     "fn partial_cmp(&self,other:&Self)->::core::option::Option<::core::cmp::Ordering>{{::core::cmp::PartialOrd::partial_cmp(&self.{fs},&\
+      other.{fs})}}",
+     fs = field_symbol
+    )
+   )
+  },
+  "Ord" =>
+  {
+   (
+    "::core::cmp::Ord".to_string(),
+    format!(
+     "fn cmp(&self,other:&Self)->::core::cmp::Ordering{{::core::cmp::Ord::cmp(&self.{fs},&other.{fs})}}",
+     fs = field_symbol
+    )
+   )
+  },
+  _ =>
+  {
+   panic!(
+    "tia not implemented feature: `delegate=\"{}\"`, supported traits are {}, Iterator, DoubleEndedIterator, ExactSizeIterator, Deref, \
+     DerefMut, AsRef, AsMut, Hash, PartialEq, Eq, PartialOrd and Ord. Use `delegate_fn=\"...\"` for the other methods. #TIA-PANIC-2004",
+    delegation,
+    FMT_TRAITS.join(", ")
+   )
+  }
+ };
+
+ format!("impl {} for {}{{{}}}", trait_path, impl_target_symbol, body)
+}
@@ -0,0 +1,100 @@
+use super::{
+ field_enum::{
+  generate_field_enum_symbol,
+  generate_variant_symbol
+ },
+ *
+};
+
+/// `self.dirty.insert(MyStructField::Foo);`, or empty if `dirty` is not used.
+pub fn generate_mark(field_symbol: &FieldSymbol, tia: &Tia) -> String
+{
+ match &tia
+  .type_params
+  .dirty
+ {
+  Some(dirty) if dirty != field_symbol =>
+  {
+   format!(
+    "self.{}.insert({}::{});",
+    dirty,
+    generate_field_enum_symbol(tia),
+    generate_variant_symbol(field_symbol)
+   )
+  },
+  _ => String::new()
+ }
+}
+
+/// The `MyStructDirty` bitset type and `is_dirty_xxxx`, `dirty_fields`, `clear_dirty` of the target type.
+pub fn generate_dirty(tia: &Tia) -> String
+{
+ let dirty = match &tia
+  .type_params
+  .dirty
+ {
+  Some(dirty) => dirty,
+  None => return String::new()
+ };
+
+ if matches!(tia.target_type, TargetType::Union)
+ {
+  panic!("tia not implemented feature: `dirty` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2006")
+ }
+
+ let field_enum_symbol = generate_field_enum_symbol(tia);
+ let dirty_symbol = format!("{}Dirty", tia.target_type_symbol);
+ let words = (tia
+  .target_fields
+  .len()
+  + 63)
+  / 64;
+ let vis = &tia.target_visibility;
+ let inherent_vis = generate_inherent_visibility(tia);
+
+ let tracked_fields = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| &target_field.field_symbol != dirty)
+  .collect::<Vec<&TargetField>>();
+
+ let is_dirty_accessors = tracked_fields
+  .iter()
+  .map(|target_field| {
+   format!(
+    "{}fn is_dirty_{}(&self)->bool{{self.{}.contains({}::{})}}",
+    inherent_vis,
+    target_field
+     .field_symbol
+     .trim_start_matches("r#"),
+    dirty,
+    field_enum_symbol,
+    generate_variant_symbol(&target_field.field_symbol)
+   )
+  })
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+
+ let all_fields = tracked_fields
+  .iter()
+  .map(|target_field| format!("{}::{}", field_enum_symbol, generate_variant_symbol(&target_field.field_symbol)))
+  .collect::<Vec<String>>()
+  .join(",");
+
+ format!(
+  "#[derive(Debug,Clone,Copy,Default,PartialEq,Eq,Hash)]{vis}struct {d}([u64;{w}]);impl {d}{{{vis}fn insert(&mut self,field:{f}){{let \
+   i=field as usize;self.0[i/64]|=1u64<<(i%64);}}{vis}fn contains(&self,field:{f})->bool{{let i=field as \
+   usize;self.0[i/64]&(1u64<<(i%64))!=0}}{vis}fn clear(&mut self){{self.0=[0u64;{w}];}}}}impl {t}{{{is_dirty}{iv}fn \
+   dirty_fields(&self)->::std::vec::Vec<{f}>{{[{all}].into_iter().filter(|field|self.{fs}.contains(*field)).collect()}}{iv}fn \
+   clear_dirty(&mut self){{self.{fs}.clear()}}}}",
+  vis = vis,
+  iv = inherent_vis,
+  d = dirty_symbol,
+  w = words,
+  f = field_enum_symbol,
+  t = tia.target_type_symbol,
+  is_dirty = is_dirty_accessors,
+  all = all_fields,
+  fs = dirty
+ )
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+/// `MyStruct` => `MyStructField`
+pub fn generate_field_enum_symbol(tia: &Tia) -> String { format!("{}Field", tia.target_type_symbol) }
+
+/// `r#type` => `Type`, `max_size` => `MaxSize`
+pub fn generate_variant_symbol(field_symbol: &FieldSymbol) -> String
+{
+ field_symbol
+  .trim_start_matches("r#")
+  .split('_')
+  .map(|word| {
+   let mut chars = word.chars();
+   match chars.next()
+   {
+    Some(head) =>
+    {
+     head
+      .to_uppercase()
+      .chain(chars)
+      .collect::<String>()
+    },
+    None => String::new()
+   }
+  })
+  .collect()
+}
+
+/// The `MyStructField` enum, one variant per field in the declaration order; it is generated only if some directive uses it.
+pub fn generate_field_enum(tia: &Tia) -> String
+{
+ if !is_field_enum_required(tia)
+ {
+  return String::new();
+ }
+
+ let field_enum_symbol = generate_field_enum_symbol(tia);
+ let variants = tia
+  .target_fields
+  .iter()
+  .map(|target_field| generate_variant_symbol(&target_field.field_symbol))
+  .collect::<Vec<String>>();
+ let names = tia
+  .target_fields
+  .iter()
+  .map(|target_field| {
+   target_field
+    .field_symbol
+    .trim_start_matches("r#")
+  })
+  .collect::<Vec<&str>>();
+
+ let all = variants
+  .iter()
+  .map(|variant| format!("{}::{}", field_enum_symbol, variant))
+  .collect::<Vec<String>>()
+  .join(",");
+ let to_name = variants
+  .iter()
+  .zip(&names)
+  .map(|(variant, name)| format!("{}::{}=>{:?},", field_enum_symbol, variant, name))
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+ let from_name = variants
+  .iter()
+  .zip(&names)
+  .map(|(variant, name)| format!("{:?}=>::core::option::Option::Some({}::{}),", name, field_enum_symbol, variant))
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+
+ format!(
+  "#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]{vis}enum {f}{{{variants}}}impl {f}{{pub const ALL:[{f};{n}]=[{all}];pub fn \
+   name(self)->&'static str{{match self{{{to_name}}}}}pub fn from_name(name:&str)->::core::option::Option<Self>{{match \
+   name{{{from_name}_=>::core::option::Option::None}}}}}}",
+  vis = tia.target_visibility,
+  f = field_enum_symbol,
+  variants = variants.join(","),
+  n = variants.len(),
+  all = all,
+  to_name = to_name,
+  from_name = from_name
+ )
+}
+
+fn is_field_enum_required(tia: &Tia) -> bool
+{
+ tia
+  .type_params
+  .on_change
+  .is_some()
+  || tia
+   .type_params
+   .dirty
+   .is_some()
+  || tia
+   .type_params
+   .reflect
+  || tia
+   .target_fields
+   .iter()
+   .any(|target_field| {
+    target_field
+     .on_change
+     .is_some()
+   })
+}
@@ -0,0 +1,66 @@
+use super::{
+ field_enum::generate_variant_symbol,
+ *
+};
+
+/// The `Lens` trait and the `Then` composition, they will be expanded by `tia::lens_types!()`.
+/// The intermediate targets of `Then` should be `'static`, it is always true for the lenses of tia because tia does not support the generic types.
+pub fn generate_lens_types() -> String
+{
+ "pub trait Lens<S:?Sized>{type Target:?Sized;fn get<'a>(&self,s:&'a S)->&'a Self::Target;fn get_mut<'a>(&self,s:&'a mut S)->&'a mut \
+  Self::Target;fn set(&self,s:&mut S,v:Self::Target) where Self::Target:Sized{*self.get_mut(s)=v;}fn \
+  then<L:Lens<Self::Target>>(self,next:L)->Then<Self,L> where Self:Sized{Then(self,next)}}#[derive(Debug,Clone,Copy,Default)]pub struct \
+  Then<A,B>(pub A,pub B);impl<S:?Sized,A:Lens<S>,B:Lens<A::Target>> Lens<S> for Then<A,B> where A::Target:'static{type Target=B::Target;fn \
+  get<'a>(&self,s:&'a S)->&'a Self::Target{self.1.get(self.0.get(s))}fn get_mut<'a>(&self,s:&'a mut S)->&'a mut \
+  Self::Target{self.1.get_mut(self.0.get_mut(s))}}"
+  .to_string()
+}
+
+/// A zero-sized lens type and `lens_xxxx()` per field, with the visibility of the field.
+pub fn generate_lenses(tia: &Tia) -> String
+{
+ let lens_module = match &tia
+  .type_params
+  .lens
+ {
+  Some(lens_module) => lens_module,
+  None => return String::new()
+ };
+
+ if matches!(tia.target_type, TargetType::Union)
+ {
+  panic!("tia not implemented feature: `lens` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2009")
+ }
+
+ let t = &tia.target_type_symbol;
+ let (lens_types, lens_fns): (Vec<String>, Vec<String>) = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| !target_field.skip)
+  .map(|target_field| {
+   let lens_symbol = format!("{}{}Lens", t, generate_variant_symbol(&target_field.field_symbol));
+   let lens_type = format!(
+    "#[derive(Debug,Clone,Copy,Default)]{vis}struct {l};impl {m}::Lens<{t}> for {l}{{type Target={ft};fn get<'a>(&self,s:&'a {t})->&'a \
+     {ft}{{&s.{fs}}}fn get_mut<'a>(&self,s:&'a mut {t})->&'a mut {ft}{{&mut s.{fs}}}}}",
+    vis = target_field.visibility,
+    l = lens_symbol,
+    m = lens_module,
+    t = t,
+    ft = target_field.field_type,
+    fs = target_field.field_symbol
+   );
+   let lens_fn = format!(
+    "{}const fn lens_{}()->{}{{{}}}",
+    target_field.visibility,
+    target_field
+     .field_symbol
+     .trim_start_matches("r#"),
+    lens_symbol,
+    lens_symbol
+   );
+   (lens_type, lens_fn)
+  })
+  .unzip();
+
+ format!("{}impl {}{{{}}}", lens_types.join(NO_SEPARATOR), t, lens_fns.join(NO_SEPARATOR))
+}
@@ -0,0 +1,209 @@
+use super::*;
+use quote::ToTokens;
+use std::collections::BTreeMap;
+use syn::spanned::Spanned;
+
+const INHERENT_MERGE_KEY: &str = "__tia_inherent";
+
+/// `MyStruct` => `__tia_merge_MyStruct`
+fn generate_merge_macro_symbol(target_type_symbol: &str) -> String { format!("__tia_merge_{}", target_type_symbol) }
+
+/// `crate :: api :: Getter < i32 >` => `["api", "Getter_i32"]`, the segments without `crate`, `self` and `super`; `` => `[]`.
+fn generate_merge_segments(trait_symbol: &TraitSymbol) -> Vec<String>
+{
+ syn::parse_str::<syn::Path>(trait_symbol)
+  .map(|path| {
+   path
+    .segments
+    .iter()
+    .skip_while(|segment| segment.ident == "crate" || segment.ident == "self" || segment.ident == "super")
+    .map(|segment| {
+     segment
+      .to_token_stream()
+      .to_string()
+      .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+      .filter(|part| !part.is_empty())
+      .collect::<Vec<&str>>()
+      .join("_")
+    })
+    .collect()
+  })
+  .unwrap_or_default()
+}
+
+/// `["api", "Getter_i32"]` => `api__Getter_i32`, `[]` => `__tia_inherent`; the arm of the merge macro.
+fn generate_merge_key(segments: &[String]) -> String
+{
+ match segments.is_empty()
+ {
+  true => INHERENT_MERGE_KEY.to_string(),
+  false => segments.join("__")
+ }
+}
+
+/// `crate :: api :: Getter < i32 >` => `[Getter_i32, api__Getter_i32]`, the keys of the path suffixes.
+/// Thus `#[tia::impl_accessors] impl Getter<i32> for MyStruct` and `impl api::Getter<i32> for MyStruct` also work, but `impl other::Getter<i32>` does not.
+fn generate_merge_keys(trait_symbol: &TraitSymbol) -> Vec<String>
+{
+ let segments = generate_merge_segments(trait_symbol);
+ match segments.is_empty()
+ {
+  true => vec![INHERENT_MERGE_KEY.to_string()],
+  false =>
+  {
+   (0..segments.len())
+    .rev()
+    .map(|start| generate_merge_key(&segments[start..]))
+    .collect()
+  },
+ }
+}
+
+/// `crate :: api :: Getter < i32 >` => `impl crate::api::Getter<i32> for MyStruct`, `` => `impl MyStruct`
+fn generate_merge_target_description(target_type_symbol: &str, trait_symbol: &TraitSymbol) -> String
+{
+ match trait_symbol.is_empty()
+ {
+  true => format!("impl {}", target_type_symbol),
+  false => format!("impl {} for {}", trait_symbol.replace(' ', ""), target_type_symbol)
+ }
+}
+
+/// A `macro_rules!` per type instead of the merged `impl`s, it will be called by `#[tia::impl_accessors]`.
+/// An arm per merged trait, and the last arm is a `compile_error!` for an `impl` which is not merged.
+/// The `use` makes the macro available in the whole module, thus the `impl` could be before the type.
+pub fn generate_merge_macros(tia: &Tia) -> String
+{
+ if tia
+  .type_params
+  .merged_traits
+  .is_empty()
+ {
+  return String::new();
+ }
+
+ // A key shared by the traits such as `Getter` of `a::Getter` and `b::Getter` is ambiguous, the longer keys such as `a__Getter` are used.
+ let mut key_to_trait_symbols = BTreeMap::<String, Vec<&TraitSymbol>>::new();
+ for trait_symbol in &tia
+  .type_params
+  .merged_traits
+ {
+  for key in generate_merge_keys(trait_symbol)
+  {
+   key_to_trait_symbols
+    .entry(key)
+    .or_default()
+    .push(trait_symbol);
+  }
+ }
+ let (key_to_trait_symbol, ambiguous_keys) = key_to_trait_symbols
+  .into_iter()
+  .fold(
+   (BTreeMap::<String, &TraitSymbol>::new(), vec![]),
+   |(mut unique, mut ambiguous), (key, trait_symbols)| {
+    match trait_symbols[..]
+    {
+     [trait_symbol] =>
+     {
+      unique.insert(key, trait_symbol);
+     },
+     _ => ambiguous.push(key)
+    }
+    (unique, ambiguous)
+   }
+  );
+ for trait_symbol in &tia
+  .type_params
+  .merged_traits
+ {
+  if !key_to_trait_symbol
+   .values()
+   .any(|other| other == &trait_symbol)
+  {
+   panic!(
+    "tia syntax error: `merge` of `{}` of `{}` could not be distinguished from the other merged traits by `#[tia::impl_accessors]`, the \
+     paths are same. Merge one of them only please. #TIA-PANIC-2013",
+    trait_symbol, tia.target_type_symbol
+   )
+  }
+ }
+
+ let arms = key_to_trait_symbol
+  .iter()
+  .map(|(key, trait_symbol)| {
+   let is_pub = trait_symbol.is_empty();
+   let header = generate_impl_header(trait_symbol, &tia.target_type_symbol, &tia.type_params);
+   let body = tia
+    .trait_to_field_accessors
+    .get(*trait_symbol)
+    .map(|field_to_accessors| generate_impl_body(trait_symbol, field_to_accessors, is_pub, tia))
+    .unwrap_or_default();
+   format!(
+    "({} $found:literal $(#[$meta:meta])*;$($items:tt)*)=>{{$(#[$meta])*{}{}$($items)*{}}};",
+    key,
+    header,
+    body,
+    generate_impl_footer()
+   )
+  })
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+ let ambiguous_arms = ambiguous_keys
+  .iter()
+  .map(|key| {
+   format!(
+    "({} $found:literal $($rest:tt)*)=>{{compile_error!(concat!(\"tia: `#[tia::impl_accessors] \",$found,\"` is ambiguous, some merged \
+     traits of `{}` have the path. Write more segments of the path please. #TIA-ERROR-2015\"));}};",
+    key, tia.target_type_symbol
+   )
+  })
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+ let expected = tia
+  .type_params
+  .merged_traits
+  .iter()
+  .map(|trait_symbol| format!("`{}`", generate_merge_target_description(&tia.target_type_symbol, trait_symbol)))
+  .collect::<Vec<String>>()
+  .join(", ");
+ let macro_symbol = generate_merge_macro_symbol(&tia.target_type_symbol);
+ format!(
+  "#[allow(unused_macros)]macro_rules! {macro_symbol}{{{arms}{ambiguous_arms}($key:ident $found:literal \
+   $($rest:tt)*)=>{{compile_error!(concat!(\"tia: `#[tia::impl_accessors] \",$found,\"` is not merged by `#[derive(Tia)]`, expected \
+   {expected}. The trait path is matched by its last segments, eg. `Getter<i32>` or `api::Getter<i32>` for `crate::api::Getter<i32>`. \
+   #TIA-ERROR-2014\"));}};}}#[allow(unused_imports)]use {macro_symbol};",
+  macro_symbol = macro_symbol,
+  arms = arms,
+  ambiguous_arms = ambiguous_arms,
+  expected = expected
+ )
+}
+
+/// `#[tia::impl_accessors] impl Trait for MyStruct { items }` => `__tia_merge_MyStruct!{ Trait "impl Trait for MyStruct" ; items }`
+/// The attributes and the items are passed through as the tokens, thus the errors in them are reported at the source location.
+pub fn generate_merge_invocation(
+ target_type_symbol: &str,
+ trait_symbol: &TraitSymbol,
+ item_impl: &syn::ItemImpl
+) -> proc_macro2::TokenStream
+{
+ // The `compile_error!` of the macro is reported at the trait path, or at the type of the inherent `impl`.
+ let span = match &item_impl.trait_
+ {
+  Some((_, path, _)) => path.span(),
+  None =>
+  {
+   item_impl
+    .self_ty
+    .span()
+  },
+ };
+ let macro_symbol = syn::Ident::new(&generate_merge_macro_symbol(target_type_symbol), span);
+ let key = syn::Ident::new(&generate_merge_key(&generate_merge_segments(trait_symbol)), span);
+ let found = syn::LitStr::new(&generate_merge_target_description(target_type_symbol, trait_symbol), span);
+ let attributes = &item_impl.attrs;
+ let items = &item_impl.items;
+ quote::quote_spanned! {span=>
+  #macro_symbol!{#key #found #(#attributes)*;#(#items)*}
+ }
+}
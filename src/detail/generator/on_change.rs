@@ -0,0 +1,145 @@
+use super::{
+ field_enum::{
+  generate_field_enum_symbol,
+  generate_variant_symbol
+ },
+ *
+};
+
+/// `let old = replace(&mut self.foo, v); self.notify(MyStructField::Foo, &old, &self.foo);`
+pub fn generate_hooked_assignment(
+ field_symbol: &FieldSymbol,
+ field_params: &FieldParams,
+ new_value: &str,
+ on_change: &OnChange,
+ tia: &Tia
+) -> String
+{
+ let access = generate_field_access(field_symbol, field_params);
+ let call = generate_call(
+  &owner_field_symbol(field_symbol, field_params),
+  on_change,
+  tia,
+  &format!("self.{}", access),
+  "self",
+  "old"
+ );
+ format!("let old=::core::mem::replace(&mut self.{},{});{}", access, new_value, call)
+}
+
+/// `rmg` with `on_change` returns `MyStructFooGuard` instead of `&mut T`; the hook is called when the guard is dropped.
+pub fn generate_guard_accessor(function_symbol: &str, field_symbol: &FieldSymbol, field_params: &FieldParams, tia: &Tia)
+ -> (String, String)
+{
+ (
+  format!(
+   "fn {}(&mut self)->{}<'_>",
+   function_symbol,
+   generate_guard_symbol(field_symbol, tia)
+  ),
+  generate_guard_construction(field_symbol, field_params, tia)
+ )
+}
+
+/// `MyStructFooGuard{ old: self.foo.clone(), owner: self }`
+pub fn generate_guard_construction(field_symbol: &FieldSymbol, field_params: &FieldParams, tia: &Tia) -> String
+{
+ format!(
+  "{}{{old: ::core::clone::Clone::clone(&self.{}),owner:self}}",
+  generate_guard_symbol(field_symbol, tia),
+  generate_field_access(field_symbol, field_params)
+ )
+}
+
+/// The guard types of the `rmg` accessors of the fields with `on_change`.
+pub fn generate_guards(tia: &Tia) -> String
+{
+ if matches!(tia.target_type, TargetType::Union)
+  && (tia
+   .type_params
+   .on_change
+   .is_some()
+   || tia
+    .target_fields
+    .iter()
+    .any(|target_field| {
+     target_field
+      .on_change
+      .is_some()
+    }))
+ {
+  panic!("tia not implemented feature: `on_change` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2005")
+ }
+
+ // Deduplicated by the field symbol and sorted, the same field might be in some traits.
+ tia
+  .trait_to_field_accessors
+  .values()
+  .flatten()
+  .filter(|(_, field_params)| {
+   field_params
+    .accessors
+    .iter()
+    .any(is_ref_mut_getter)
+  })
+  .filter_map(|(field_symbol, field_params)| {
+   field_params
+    .on_change
+    .as_ref()
+    .map(|on_change| (field_symbol, generate_guard(field_symbol, field_params, on_change, tia)))
+  })
+  .collect::<std::collections::BTreeMap<&FieldSymbol, String>>()
+  .into_values()
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR)
+}
+
+fn generate_guard(field_symbol: &FieldSymbol, field_params: &FieldParams, on_change: &OnChange, tia: &Tia) -> String
+{
+ let guard_symbol = generate_guard_symbol(field_symbol, tia);
+ let access = generate_field_access(field_symbol, field_params);
+ let call = generate_call(
+  &owner_field_symbol(field_symbol, field_params),
+  on_change,
+  tia,
+  &format!("self.owner.{}", access),
+  "self.owner",
+  "self.old"
+ );
+ format!(
+  "{vis}struct {g}<'a>{{owner:&'a mut {t},old:{ft}}}impl ::core::ops::Deref for {g}<'_>{{type Target={ft};fn \
+   deref(&self)->&{ft}{{&self.owner.{fs}}}}}impl ::core::ops::DerefMut for {g}<'_>{{fn deref_mut(&mut self)->&mut {ft}{{&mut \
+   self.owner.{fs}}}}}impl ::core::ops::Drop for {g}<'_>{{fn drop(&mut self){{{call}}}}}",
+  vis = tia.target_visibility,
+  g = guard_symbol,
+  t = tia.target_type_symbol,
+  ft = field_params.field_type,
+  fs = access,
+  call = call
+ )
+}
+
+/// `self.notify(MyStructField::Foo, &old, &new);`, with `if old != new` for `on_change_if_changed`.
+fn generate_call(field_symbol: &FieldSymbol, on_change: &OnChange, tia: &Tia, new: &str, owner: &str, old: &str) -> String
+{
+ let call = format!(
+  "{}.{}({}::{},&{},&{});",
+  owner,
+  on_change.method,
+  generate_field_enum_symbol(tia),
+  generate_variant_symbol(field_symbol),
+  old,
+  new
+ );
+ match on_change.skip_unchanged
+ {
+  true => format!("if {}!={}{{{}}}", old, new, call),
+  false => call
+ }
+}
+
+/// `MyStruct`, `max_size` => `MyStructMaxSizeGuard`
+fn generate_guard_symbol(field_symbol: &FieldSymbol, tia: &Tia) -> String
+{
+ format!("{}{}Guard", tia.target_type_symbol, generate_variant_symbol(field_symbol))
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+/// `FIELDS`, `field_names`, `get_by_name` and `set_by_name` of the target type.
+pub fn generate_reflect(tia: &Tia) -> String
+{
+ if !tia
+  .type_params
+  .reflect
+ {
+  return String::new();
+ }
+
+ if matches!(tia.target_type, TargetType::Union)
+ {
+  panic!("tia not implemented feature: `reflect` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2007")
+ }
+
+ let names = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| !target_field.skip)
+  .map(|target_field| {
+   format!(
+    "{:?}",
+    target_field
+     .field_symbol
+     .trim_start_matches("r#")
+   )
+  })
+  .collect::<Vec<String>>()
+  .join(",");
+
+ let getters = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| !target_field.skip)
+  .filter(|target_field| {
+   has_accessor(&target_field.field_symbol, tia, |accessor| {
+    matches!(accessor, Accessor::Getter { .. })
+   })
+  })
+  .map(|target_field| {
+   format!(
+    "{:?}=>::core::option::Option::Some(&self.{}),",
+    target_field
+     .field_symbol
+     .trim_start_matches("r#"),
+    target_field.field_symbol
+   )
+  })
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+
+ // The fields with a validated setter are not settable by name, it would bypass the validation.
+ let setters = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| !target_field.skip)
+  .filter(|target_field| {
+   has_accessor(&target_field.field_symbol, tia, |accessor| {
+    matches!(accessor, Accessor::Setter {
+     svp: None,
+     ..
+    })
+   })
+    && !has_accessor(&target_field.field_symbol, tia, |accessor| {
+     matches!(accessor, Accessor::Setter {
+      svp: Some(_),
+      ..
+     })
+    })
+  })
+  .map(|target_field| generate_set_by_name_arm(target_field, tia))
+  .collect::<Vec<String>>()
+  .join(NO_SEPARATOR);
+
+ format!(
+  "impl {t}{{{iv}const FIELDS:&'static [&'static str]=&[{names}];{iv}fn field_names()->&'static [&'static str]{{Self::FIELDS}}{iv}fn \
+   get_by_name(&self,name:&str)->::core::option::Option<&dyn ::core::any::Any>{{match \
+   name{{{getters}_=>::core::option::Option::None}}}}{iv}fn set_by_name(&mut self,name:&str,v: ::std::boxed::Box<dyn \
+   ::core::any::Any>)->::core::result::Result<(),::std::boxed::Box<dyn ::core::any::Any>>{{match \
+   name{{{setters}_=>::core::result::Result::Err(v)}}}}}}",
+  t = tia.target_type_symbol,
+  iv = generate_inherent_visibility(tia),
+  names = names,
+  getters = getters,
+  setters = setters
+ )
+}
+
+fn generate_set_by_name_arm(target_field: &TargetField, tia: &Tia) -> String
+{
+ let field_symbol = &target_field.field_symbol;
+ let hooked = find_field_params(field_symbol, tia).find_map(|field_params| {
+  field_params
+   .on_change
+   .as_ref()
+   .map(|on_change| (field_params, on_change))
+ });
+ let assignment = match hooked
+ {
+  Some((field_params, on_change)) => on_change::generate_hooked_assignment(field_symbol, field_params, "v", on_change, tia),
+  None => format!("self.{}=v;", field_symbol)
+ };
+ format!(
+  "{:?}=>{{let v=*v.downcast::<{}>()?;{}{}::core::result::Result::Ok(())}}",
+  field_symbol.trim_start_matches("r#"),
+  target_field.field_type,
+  assignment,
+  dirty::generate_mark(field_symbol, tia)
+ )
+}
@@ -0,0 +1,117 @@
+use super::*;
+
+/// ( signature, body ) of the virtual accessors of the trait.
+pub fn generate_virtual_accessors(trait_symbol: &TraitSymbol, tia: &Tia) -> Vec<(String, String)>
+{
+ tia
+  .type_params
+  .virtual_accessors
+  .iter()
+  .filter(|virtual_accessor| &virtual_accessor.trait_symbol == trait_symbol)
+  .map(generate_virtual_accessor)
+  .collect()
+}
+
+fn generate_virtual_accessor(virtual_accessor: &VirtualAccessor) -> (String, String)
+{
+ let VirtualAccessor {
+  accessor,
+  field_type,
+  expr,
+  ..
+ } = virtual_accessor;
+ match accessor
+ {
+  Accessor::Getter {
+   fsp,
+   ptp
+  } =>
+  {
+   let function_symbol = generate_function_symbol(&FieldSymbol::new(), fsp);
+   let signature = match ptp
+   {
+    GetterParameterTypePolicy::Move => format!("fn {}(self)->{}", function_symbol, field_type),
+    GetterParameterTypePolicy::Value | GetterParameterTypePolicy::Clone | GetterParameterTypePolicy::Into(_) =>
+    {
+     format!("fn {}(&self)->{}", function_symbol, field_type)
+    },
+    GetterParameterTypePolicy::Ref => format!("fn {}(&self)->&{}", function_symbol, field_type),
+    GetterParameterTypePolicy::RefMut => format!("fn {}(&mut self)->&mut {}", function_symbol, field_type)
+   };
+   (signature, expr.clone())
+  },
+  Accessor::Setter {
+   fsp,
+   ptp,
+   ..
+  } =>
+  {
+   let function_symbol = generate_function_symbol(&FieldSymbol::new(), fsp);
+   match ptp
+   {
+    SetterParameterTypePolicy::Value => (format!("fn {}(&mut self,v:{})", function_symbol, field_type), expr.clone()),
+    SetterParameterTypePolicy::RefClone => (format!("fn {}(&mut self,v:&{})", function_symbol, field_type), expr.clone()),
+    SetterParameterTypePolicy::Into =>
+    {
+     (
+      format!("fn {}<T:Into<{}>>(&mut self,v:T)", function_symbol, field_type),
+      format!("let v:{}=v.into();{}", field_type, expr)
+     )
+    },
+    SetterParameterTypePolicy::TryInto =>
+    {
+     (
+      format!(
+       "fn {}<T: ::core::convert::TryInto<{}>>(&mut self,v:T)->::core::result::Result<(),{}>",
+       function_symbol,
+       field_type,
+       generate_setter_error_type(field_type, ptp, None).unwrap()
+      ),
+      format!(
+       "let v:{}=::core::convert::TryInto::try_into(v)?;{};::core::result::Result::Ok(())",
+       field_type,
+       trim_statement(expr)
+      )
+     )
+    },
+    SetterParameterTypePolicy::AsRef(borrowed_type) =>
+    {
+     let borrowed_type = generate_borrowed_type(field_type, borrowed_type);
+     (
+      format!("fn {}<T:AsRef<{}>>(&mut self,v:T)", function_symbol, borrowed_type),
+      format!(
+       "let v:{}=::std::borrow::ToOwned::to_owned(AsRef::<{}>::as_ref(&v));{}",
+       field_type, borrowed_type, expr
+      )
+     )
+    },
+    SetterParameterTypePolicy::Parse =>
+    {
+     (
+      format!(
+       "fn {}(&mut self,v:&str)->::core::result::Result<(),{}>",
+       function_symbol,
+       generate_setter_error_type(field_type, ptp, None).unwrap()
+      ),
+      format!(
+       "let v:{}=::core::str::FromStr::from_str(v)?;{};::core::result::Result::Ok(())",
+       field_type,
+       trim_statement(expr)
+      )
+     )
+    },
+   }
+  },
+  Accessor::Delegate {
+   ..
+  } => panic!("tia implementation bug: a delegate could not be virtual. #TIA-PANIC-2011")
+ }
+}
+
+/// `self.x = v;` => `self.x = v`, to append the `Ok(())` without `;;`.
+fn trim_statement(expr: &str) -> &str
+{
+ expr
+  .trim_end()
+  .trim_end_matches(';')
+}
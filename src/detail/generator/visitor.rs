@@ -0,0 +1,108 @@
+use super::*;
+
+/// `visit_fields` over the fields with a getter and `visit_fields_mut` over the fields with `rmg`.
+pub fn generate_visitor(tia: &Tia) -> String
+{
+ let visitor_policy = match &tia
+  .type_params
+  .visitor
+ {
+  Some(visitor_policy) => visitor_policy,
+  None => return String::new()
+ };
+
+ if matches!(tia.target_type, TargetType::Union)
+ {
+  panic!("tia not implemented feature: `visitor` for union, please write PR or Issue if you want the feature. #TIA-PANIC-2008")
+ }
+
+ let (visitor_type, visitor_mut_type, visit, visit_mut) = match visitor_policy
+ {
+  VisitorPolicy::Closure =>
+  {
+   (
+    "impl FnMut(&'static str,&dyn ::core::any::Any)".to_string(),
+    "impl FnMut(&'static str,&mut dyn ::core::any::Any)".to_string(),
+    "v",
+    "v"
+   )
+  },
+  VisitorPolicy::Trait(trait_path) =>
+  {
+   (
+    format!("impl {}", trait_path),
+    format!("impl {}", trait_path),
+    "v.visit",
+    "v.visit_mut"
+   )
+  },
+ };
+
+ let visits = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| {
+   has_accessor(&target_field.field_symbol, tia, |accessor| {
+    matches!(accessor, Accessor::Getter { .. })
+   })
+  })
+  .map(|target_field| {
+   format!(
+    "{}({:?},&self.{});",
+    visit,
+    target_field
+     .field_symbol
+     .trim_start_matches("r#"),
+    target_field.field_symbol
+   )
+  })
+  .collect::<Vec<String>>();
+
+ let visits_mut = tia
+  .target_fields
+  .iter()
+  .filter(|target_field| has_accessor(&target_field.field_symbol, tia, is_ref_mut_getter))
+  .map(|target_field| {
+   let field_symbol = &target_field.field_symbol;
+   let name = field_symbol.trim_start_matches("r#");
+   let hooked_field_params = find_field_params(field_symbol, tia).find(|field_params| {
+    field_params
+     .on_change
+     .is_some()
+   });
+   let call = match hooked_field_params
+   {
+    Some(field_params) =>
+    {
+     format!(
+      "{{let mut guard={};{}({:?},&mut *guard);}}",
+      on_change::generate_guard_construction(field_symbol, field_params, tia),
+      visit_mut,
+      name
+     )
+    },
+    None => format!("{}({:?},&mut self.{});", visit_mut, name, field_symbol)
+   };
+   format!("{}{}", dirty::generate_mark(field_symbol, tia), call)
+  })
+  .collect::<Vec<String>>();
+
+ format!(
+  "impl {t}{{{iv}fn visit_fields(&self,v:&mut {v}){{{visits}}}{iv}fn visit_fields_mut(&mut self,v:&mut {vm}){{{visits_mut}}}}}",
+  t = tia.target_type_symbol,
+  iv = generate_inherent_visibility(tia),
+  v = visitor_type,
+  visits = generate_visits_body(visits),
+  vm = visitor_mut_type,
+  visits_mut = generate_visits_body(visits_mut)
+ )
+}
+
+fn generate_visits_body(visits: Vec<String>) -> String
+{
+ match visits.is_empty()
+ {
+  true => "let _=v;".to_string(),
+  false => visits.join(NO_SEPARATOR)
+ }
+}
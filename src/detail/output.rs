@@ -0,0 +1,982 @@
+use super::*;
+use once_cell::sync::Lazy;
+use quote::ToTokens;
+use std::{
+ path::{
+  Component,
+  Path,
+  PathBuf
+ },
+ sync::Mutex
+};
+
+const OUT_DIR_VARIABLE: &str = "$OUT_DIR";
+const TIA_DERIVE: &str = "Tia";
+/// The first line of the generated file, eg. `// tia:input-hash=0123456789abcdef`.
+const INPUT_HASH_HEADER: &str = "// tia:input-hash=";
+/// FNV-1a 64, it is stable across the builds and the toolchains unlike `DefaultHasher`.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+/// The directories of the sources, eg. `src/lib.rs` and `tests/test.rs` are the crate roots.
+const SOURCE_DIRS: [&str; 4] = ["src", "tests", "examples", "benches"];
+const SRC_DIR: &str = "src";
+const BIN_DIR: &str = "bin";
+/// `src/main.rs`, `src/bin/tool/main.rs` and `tests/suite/main.rs` are the crate roots, but `src/api/main.rs` is `api::main`.
+const MAIN_STEM: &str = "main";
+const MAIN_FILE: &str = "main.rs";
+/// The file stems which are not a module, eg. `src/lib.rs` is the crate root and `src/api/mod.rs` is `api`.
+const NOT_MODULE_STEMS: [&str; 2] = ["lib", "mod"];
+const RUSTFMT_CONFIG_FILES: [&str; 2] = ["rustfmt.toml", ".rustfmt.toml"];
+
+/// The generated files written in this build and their owners, see `GeneratedFile::owner`.
+static WRITTEN_FILES: Lazy<Mutex<HashMap<PathBuf, String>>> = Lazy::new(Default::default);
+
+/// The generated file of a type.
+pub struct GeneratedFile
+{
+ /// `{CARGO_MANIFEST_DIR}/src/.tia/{crate}/{module/path}/{TargetTypeSymbol}.rs`
+ pub path:  PathBuf,
+ /// The type which writes the file; `src/api.rs` of the type, thus a type could rewrite its file in a proc-macro server.
+ /// It has `#{input_hash}` if the source file is unknown, or the type could not be distinguished from the same-named types in the file.
+ pub owner: String
+}
+
+/// The generated file of the type, it should be called in the proc-macro.
+/// It uses the source file of the macro call to find the module path.
+pub fn generated_file(target_type_symbol: &str, input_hash: &str) -> GeneratedFile
+{
+ let config = config::config();
+ let crate_name = std::env::var("CARGO_CRATE_NAME")
+  .or_else(|_| std::env::var("CARGO_PKG_NAME"))
+  .unwrap_or_default();
+ let source_file = local_file();
+ let inline_modules = source_file
+  .as_deref()
+  .and_then(|source_file| std::fs::read_to_string(source_file).ok())
+  .and_then(|source| syn::parse_file(&source).ok())
+  .map(|file| inline_module_path(&file, target_type_symbol, input_hash, &config));
+ let relative_source_file = source_file
+  .as_deref()
+  .and_then(|source_file| relative_source_file(source_file, &config.manifest_dir));
+ let path = generated_file_path_of(
+  &config,
+  &crate_name,
+  relative_source_file.as_deref(),
+  inline_modules
+   .as_ref()
+   .map(|modules| {
+    match modules
+    {
+     Ok(modules) | Err(modules) => &modules[..]
+    }
+   })
+   .unwrap_or_default(),
+  target_type_symbol
+ );
+ let owner = match (source_file, inline_modules)
+ {
+  (Some(source_file), Some(Ok(_))) =>
+  {
+   source_file
+    .display()
+    .to_string()
+  },
+  (Some(source_file), _) => format!("{}#{}", source_file.display(), input_hash),
+  (None, _) => format!("#{}", input_hash)
+ };
+ GeneratedFile {
+  path,
+  owner
+ }
+}
+
+/// The source file of the macro call, see `build.rs` for `cfg(tia_local_file)`.
+#[cfg(tia_local_file)]
+#[clippy::msrv = "1.88"]
+fn local_file() -> Option<PathBuf> { pm::Span::call_site().local_file() }
+
+/// `proc_macro::Span::local_file` is not available before 1.88, the generated files are in the flat layout.
+#[cfg(not(tia_local_file))]
+fn local_file() -> Option<PathBuf> { None }
+
+/// `/path/to/my_crate/src/api.rs`, or `my_crate/src/api.rs` in the workspace `/path/to` => `src/api.rs`
+/// The path of `local_file` could be relative to the current directory, it is the workspace root in the build.
+fn relative_source_file(source_file: &Path, manifest_dir: &Path) -> Option<PathBuf>
+{
+ let source_file = match source_file.is_absolute()
+ {
+  true => source_file.to_path_buf(),
+  false =>
+  {
+   std::env::current_dir()
+    .ok()?
+    .join(source_file)
+  },
+ };
+ source_file
+  .strip_prefix(manifest_dir)
+  .ok()
+  .map(Path::to_path_buf)
+}
+
+/// The path of the generated file of the type in `source_file` of the crate `crate_name`, it could be called outside of the proc-macro.
+/// `config` is the config of the crate, see `config::config_of`.
+/// `source_file` is relative to `CARGO_MANIFEST_DIR` such as `src/api.rs`.
+/// `inline_modules` is the path of `mod api { ... }` in the source file, see also `inline_module_path`.
+/// The modules are not used without `cfg(tia_local_file)`, thus `cargo tia` finds the same path as the proc-macro.
+pub fn generated_file_path_of(
+ config: &config::Config,
+ crate_name: &str,
+ source_file: Option<&Path>,
+ inline_modules: &[String],
+ target_type_symbol: &str
+) -> PathBuf
+{
+ let (modules, inline_modules) = match cfg!(tia_local_file)
+ {
+  true => (module_path(source_file, &config.manifest_dir), inline_modules),
+  false => (vec![], &[][..])
+ };
+ modules
+  .iter()
+  .chain(inline_modules)
+  .fold(resolve_out_dir(config).join(crate_name), |path, module| path.join(module))
+  .join(format!("{}.rs", target_type_symbol))
+}
+
+/// `mod api { #[derive(Tia)] struct MyStruct; }` => `["api"]`, the inline modules of the type in the source file.
+/// The same-named types in the different inline modules are distinguished by `input_hash`.
+/// `Err` has the common modules of them if it could not, eg. they differ only in the fields removed by `cfg`.
+pub fn inline_module_path(
+ file: &syn::File,
+ target_type_symbol: &str,
+ input_hash: &str,
+ config: &config::Config
+) -> Result<Vec<String>, Vec<String>>
+{
+ let mut inputs = vec![];
+ collect_derive_inputs(&file.items, &[], &mut inputs);
+ let candidates = inputs
+  .into_iter()
+  .filter(|(_, input)| input.ident == target_type_symbol)
+  .collect::<Vec<(Vec<String>, syn::DeriveInput)>>();
+ let is_unique = candidates
+  .iter()
+  .all(|(modules, _)| modules == &candidates[0].0);
+ match is_unique
+ {
+  true =>
+  {
+   Ok(
+    candidates
+     .into_iter()
+     .next()
+     .map(|(modules, _)| modules)
+     .unwrap_or_default()
+   )
+  },
+  false =>
+  {
+   candidates
+    .iter()
+    .find(|(_, input)| self::input_hash(input, config) == input_hash)
+    .map(|(modules, _)| modules.clone())
+    .ok_or_else(|| {
+     candidates
+      .iter()
+      .map(|(modules, _)| &modules[..])
+      .reduce(|common, modules| {
+       let length = common
+        .iter()
+        .zip(modules)
+        .take_while(|(a, b)| a == b)
+        .count();
+       &common[..length]
+      })
+      .unwrap_or_default()
+      .to_vec()
+    })
+  },
+ }
+}
+
+/// The types with `#[derive(Tia)]` or `#[derive(tia::Tia)]` and their inline modules, the inline modules are searched recursively.
+pub fn collect_derive_inputs(items: &[syn::Item], modules: &[String], inputs: &mut Vec<(Vec<String>, syn::DeriveInput)>)
+{
+ for item in items
+ {
+  let attrs = match item
+  {
+   syn::Item::Struct(item) => &item.attrs,
+   syn::Item::Enum(item) => &item.attrs,
+   syn::Item::Union(item) => &item.attrs,
+   syn::Item::Mod(syn::ItemMod {
+    ident,
+    content: Some((_, items)),
+    ..
+   }) =>
+   {
+    let mut modules = modules.to_vec();
+    modules.push(ident.to_string());
+    collect_derive_inputs(items, &modules, inputs);
+    continue;
+   },
+   _ => continue
+  };
+  if attrs
+   .iter()
+   .any(is_tia_derive)
+  {
+   if let Ok(input) = syn::parse2::<syn::DeriveInput>(item.to_token_stream())
+   {
+    inputs.push((modules.to_vec(), input));
+   }
+  }
+ }
+}
+
+fn is_tia_derive(attr: &syn::Attribute) -> bool
+{
+ match attr.parse_meta()
+ {
+  Ok(syn::Meta::List(list))
+   if list
+    .path
+    .is_ident("derive") =>
+  {
+   list
+    .nested
+    .iter()
+    .any(|nested| {
+     matches!(
+      nested,
+      syn::NestedMeta::Meta(syn::Meta::Path(path))
+       if path.segments.last().map(|segment| segment.ident == TIA_DERIVE).unwrap_or_default()
+     )
+    })
+  },
+  _ => false
+ }
+}
+
+/// `out_dir` of the config rooted at `manifest_dir` of the config, or `OUT_DIR` of the build script if it starts with `$OUT_DIR`.
+pub fn resolve_out_dir(config: &config::Config) -> PathBuf
+{
+ match config
+  .out_dir
+  .strip_prefix(OUT_DIR_VARIABLE)
+ {
+  Some(rest) =>
+  {
+   let out_dir = std::env::var("OUT_DIR").unwrap_or_else(|_| {
+    panic!(
+     r#"tia config error: `out_dir = "{}"` requires `OUT_DIR`, add a build script such as `build.rs` to the crate please. #TIA-PANIC-4007"#,
+     config.out_dir
+    )
+   });
+   PathBuf::from(out_dir).join(rest.trim_start_matches(['/', '\\']))
+  },
+  None =>
+  {
+   config
+    .manifest_dir
+    .join(&config.out_dir)
+  },
+ }
+}
+
+/// `src/api/config.rs` => `["api", "config"]`, `src/lib.rs` or `tests/test.rs` => `[]`; `source_file` is relative to `manifest_dir`.
+/// The inline modules such as `mod api { ... }` could not be found, the types in them are same as the types of the file.
+fn module_path(source_file: Option<&Path>, manifest_dir: &Path) -> Vec<String>
+{
+ let components = source_file
+  .map(|source_file| {
+   source_file
+    .components()
+    .filter_map(|component| {
+     match component
+     {
+      Component::Normal(name) =>
+      {
+       Some(
+        name
+         .to_string_lossy()
+         .to_string()
+       )
+      },
+      _ => None
+     }
+    })
+    .collect::<Vec<String>>()
+  })
+  .unwrap_or_default();
+ let (file, dirs) = match components.split_last()
+ {
+  Some(split) => split,
+  None => return vec![]
+ };
+ let stem = Path::new(file)
+  .file_stem()
+  .map(|stem| {
+   stem
+    .to_string_lossy()
+    .to_string()
+  })
+  .unwrap_or_default();
+
+ // The modules are the directories under the source directory such as `src`, it is the first component.
+ // Thus `src/tests/mod.rs` is the module `tests`, it is not a crate root in `tests/`.
+ let (source_dir, modules) = match dirs.split_first()
+ {
+  Some((source_dir, modules)) if SOURCE_DIRS.contains(&source_dir.as_str()) => (source_dir, modules),
+  _ => return vec![]
+ };
+ // `src/bin/tool.rs` and `tests/test.rs` are the crate roots, `src/bin/tool/` and `tests/suite/` with `main.rs` are the directories of the crate roots.
+ // A directory without `main.rs` such as `tests/common/` is a module shared by the crates, eg. `mod common;` in some tests.
+ let is_crate_dir = |crate_dir: PathBuf| {
+  manifest_dir
+   .join(crate_dir)
+   .join(MAIN_FILE)
+   .is_file()
+ };
+ let (is_crate_root, modules) = match source_dir.as_str()
+ {
+  SRC_DIR =>
+  {
+   match modules
+   {
+    [dir] if dir == BIN_DIR => (true, modules),
+    [dir, crate_dir, rest @ ..]
+     if dir == BIN_DIR
+      && is_crate_dir(
+       [source_dir, dir, crate_dir]
+        .iter()
+        .collect()
+      ) =>
+    {
+     (false, rest)
+    },
+    [dir, rest @ ..] if dir == BIN_DIR => (false, rest),
+    _ => (false, modules)
+   }
+  },
+  _ =>
+  {
+   match modules
+   {
+    [] => (true, modules),
+    [crate_dir, rest @ ..]
+     if is_crate_dir(
+      [source_dir, crate_dir]
+       .iter()
+       .collect()
+     ) =>
+    {
+     (false, rest)
+    },
+    _ => (false, modules)
+   }
+  },
+ };
+ if is_crate_root || (stem == MAIN_STEM && modules.is_empty())
+ {
+  return vec![];
+ }
+
+ let mut module_path = modules.to_vec();
+ if !NOT_MODULE_STEMS.contains(&stem.as_str())
+ {
+  module_path.push(stem);
+ }
+ module_path
+}
+
+/// The hash of the input of `derive(Tia)`; the tokens of the type, the version of tia and the config which changes the generated code.
+/// The tokens are canonicalized, thus the hash is same in the proc-macro and in `cargo tia` which parses the source file.
+pub fn input_hash(i: &syn::DeriveInput, config: &config::Config) -> String
+{
+ let mut tokens = String::new();
+ canonicalize_tokens(i.to_token_stream(), &mut tokens);
+ let input = format!("{};{};{}", tokens, env!("CARGO_PKG_VERSION"), config.fingerprint());
+ let hash = input
+  .bytes()
+  .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME));
+ format!("{:016x}", hash)
+}
+
+/// The tokens separated by a space; the spacing of the compiler and of the source file are different.
+/// `#[doc = "..."]` and `#[derive(...)]` are skipped, they do not change the generated code and the compiler might remove them.
+fn canonicalize_tokens(tokens: proc_macro2::TokenStream, canonical: &mut String)
+{
+ let mut tokens = tokens
+  .into_iter()
+  .peekable();
+ while let Some(token) = tokens.next()
+ {
+  match token
+  {
+   proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '#' =>
+   {
+    let is_skipped = matches!(
+     tokens.peek(),
+     Some(proc_macro2::TokenTree::Group(group))
+      if group.delimiter() == proc_macro2::Delimiter::Bracket
+       && matches!(
+        group.stream().into_iter().next(),
+        Some(proc_macro2::TokenTree::Ident(ident)) if ident == "doc" || ident == "derive"
+       )
+    );
+    match is_skipped
+    {
+     true =>
+     {
+      tokens.next();
+     },
+     false => canonical.push_str("# ")
+    }
+   },
+   proc_macro2::TokenTree::Group(group) =>
+   {
+    let (open, close) = match group.delimiter()
+    {
+     proc_macro2::Delimiter::Parenthesis => ("( ", ") "),
+     proc_macro2::Delimiter::Brace => ("{ ", "} "),
+     proc_macro2::Delimiter::Bracket => ("[ ", "] "),
+     proc_macro2::Delimiter::None => ("", "")
+    };
+    canonical.push_str(open);
+    canonicalize_tokens(group.stream(), canonical);
+    canonical.push_str(close);
+   },
+   token =>
+   {
+    canonical.push_str(&token.to_string());
+    canonical.push(' ');
+   }
+  }
+ }
+}
+
+/// The input hash in the header of the generated file, `None` if the file or the header is not found.
+pub fn read_input_hash(path: &Path) -> Option<String>
+{
+ let source = std::fs::read_to_string(path).ok()?;
+ source
+  .lines()
+  .next()?
+  .strip_prefix(INPUT_HASH_HEADER)
+  .map(|hash| {
+   hash
+    .trim()
+    .to_string()
+  })
+}
+
+/// The state of the generated file of a type, see also `include_state`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum IncludeState
+{
+ /// The input hash of the file is same as the current input.
+ UpToDate,
+ /// The type or the directives were changed after the file was generated; the input hash of the file.
+ Stale(String),
+ /// The file was generated by an older tia or it is not a generated file.
+ NoInputHash,
+ /// The file is not generated yet.
+ NotFound
+}
+
+/// `IncludeState` of the generated file at `path` for the current `input_hash`.
+pub fn include_state(path: &Path, input_hash: &str) -> IncludeState
+{
+ match read_input_hash(path)
+ {
+  Some(file_hash) if file_hash == input_hash => IncludeState::UpToDate,
+  Some(file_hash) => IncludeState::Stale(file_hash),
+  None if path.exists() => IncludeState::NoInputHash,
+  None => IncludeState::NotFound
+ }
+}
+
+/// Write `source` to the path atomically; the temporary file is renamed to the path after it is written (and prettified).
+/// The first line is the header with `input_hash`, see also `read_input_hash`.
+/// It panics if the path is already written by another owner in this build, the generated files of the types would be same.
+pub fn write_file(target_type_symbol: &str, file: &GeneratedFile, input_hash: &str, source: &str, config: &config::Config)
+{
+ let path = file
+  .path
+  .as_path();
+ if let Some(owner) = WRITTEN_FILES
+  .lock()
+  .unwrap_or_else(|e| e.into_inner())
+  .insert(
+   path.to_path_buf(),
+   file
+    .owner
+    .clone()
+  )
+  .filter(|owner| owner != &file.owner)
+ {
+  panic!(
+   "tia output error: the generated file `{}` of `{}` is already written by another `{}` in this build, `{}` and `{}`. Rename one of them \
+    please. #TIA-PANIC-4012",
+   path.display(),
+   target_type_symbol,
+   target_type_symbol,
+   owner,
+   file.owner
+  )
+ }
+
+ let directory = path
+  .parent()
+  .unwrap_or_else(|| Path::new("."));
+ std::fs::create_dir_all(directory).unwrap_or_else(|e| {
+  panic!(
+   "tia could not create the directory `{}`. ({}) #TIA-PANIC-4008",
+   directory.display(),
+   e
+  )
+ });
+
+ let formatter = config.pretty_formatter();
+ let (source, prettyplease_result) = match formatter
+ {
+  Some(config::Formatter::Prettyplease) =>
+  {
+   match pretty_print(source)
+   {
+    Ok(pretty_source) => (pretty_source, Some(Ok(()))),
+    Err(e) => (source.to_string(), Some(Err(e)))
+   }
+  },
+  _ => (source.to_string(), None)
+ };
+ let source = format!("{}{}\n{}", INPUT_HASH_HEADER, input_hash, source);
+
+ let temporary_path = path.with_extension(format!("rs.{}.tmp", std::process::id()));
+ std::fs::write(&temporary_path, source).unwrap_or_else(|e| {
+  panic!(
+   "tia could not write the file `{}`. ({}) #TIA-PANIC-4009",
+   temporary_path.display(),
+   e
+  )
+ });
+
+ let result = match formatter
+ {
+  None => None,
+  Some(config::Formatter::Prettyplease) => prettyplease_result.map(|result| ("prettyplease", result)),
+  Some(config::Formatter::Rustfmt) => Some(("rustfmt", rustfmt(&temporary_path, config)))
+ };
+
+ std::fs::rename(&temporary_path, path).unwrap_or_else(|e| {
+  let _ = std::fs::remove_file(&temporary_path);
+  panic!("tia could not write the file `{}`. ({}) #TIA-PANIC-4011", path.display(), e)
+ });
+
+ eprintln!("{}", log_message(target_type_symbol, path, &result));
+}
+
+/// `[proc-macro:tia +file-pretty Target: MyStruct => path ; prettyplease <OK> ]`, `<NG>` with the reason if the formatter failed.
+fn log_message(target_type_symbol: &str, path: &Path, result: &Option<(&str, Result<(), String>)>) -> String
+{
+ match result
+ {
+  Some((formatter, Ok(()))) =>
+  {
+   format!(
+    "[proc-macro:tia +file-pretty Target: {} => {} ; {} <OK> ]",
+    target_type_symbol,
+    path.display(),
+    formatter
+   )
+  },
+  Some((formatter, Err(e))) =>
+  {
+   format!(
+    "[proc-macro:tia +file-pretty Target: {} => {} ; {} <NG> {} ]",
+    target_type_symbol,
+    path.display(),
+    formatter,
+    e
+   )
+  },
+  None => format!("[proc-macro:tia +file Target: {} => {}]", target_type_symbol, path.display())
+ }
+}
+
+/// The in-process pretty printing, it fails if the generated code could not be parsed.
+fn pretty_print(source: &str) -> Result<String, String>
+{
+ syn::parse_file(source)
+  .map(|file| prettyplease::unparse(&file))
+  .map_err(|e| e.to_string())
+}
+
+/// `rustfmt -q --config-path rustfmt.toml path`, it fails if `rustfmt` could not be run or it exits with an error.
+/// The config is searched from `CARGO_MANIFEST_DIR` instead of the file, thus it is found even if `out_dir` is outside of the package.
+/// The edition of the crate is used if the config does not have `edition`.
+fn rustfmt(path: &Path, config: &config::Config) -> Result<(), String>
+{
+ let rustfmt_config = find_rustfmt_config(&config.manifest_dir);
+ let has_edition = rustfmt_config
+  .as_deref()
+  .and_then(|rustfmt_config| std::fs::read_to_string(rustfmt_config).ok())
+  .and_then(|source| {
+   source
+    .parse::<toml::Value>()
+    .ok()
+  })
+  .map(|value| {
+   value
+    .get("edition")
+    .is_some()
+  })
+  .unwrap_or_default();
+ let mut command = std::process::Command::new("rustfmt");
+ command.arg("-q");
+ if let Some(rustfmt_config) = &rustfmt_config
+ {
+  command
+   .arg("--config-path")
+   .arg(rustfmt_config);
+ }
+ if let (false, Some(edition)) = (has_edition, &config.edition)
+ {
+  command
+   .arg("--edition")
+   .arg(edition);
+ }
+ let status = command
+  .arg(path)
+  .stdin(std::process::Stdio::null())
+  .stdout(std::process::Stdio::null())
+  .stderr(std::process::Stdio::null())
+  .status()
+  .map_err(|e| format!("rustfmt could not be run: {}", e))?;
+ match status.success()
+ {
+  true => Ok(()),
+  false => Err(format!("rustfmt exited with {}", status))
+ }
+}
+
+/// `rustfmt.toml` or `.rustfmt.toml` in `manifest_dir` or its parents such as the workspace root, same as the search of `rustfmt`.
+fn find_rustfmt_config(manifest_dir: &Path) -> Option<PathBuf>
+{
+ manifest_dir
+  .ancestors()
+  .flat_map(|dir| RUSTFMT_CONFIG_FILES.map(|file| dir.join(file)))
+  .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests
+{
+ use super::*;
+
+ #[test]
+ fn module_paths()
+ {
+  let manifest_dir = std::env::temp_dir().join(format!("tia-module-path-{}", std::process::id()));
+  for crate_root in ["src/bin/x/main.rs", "tests/suite/main.rs"]
+  {
+   let crate_root = manifest_dir.join(crate_root);
+   std::fs::create_dir_all(
+    crate_root
+     .parent()
+     .unwrap()
+   )
+   .unwrap();
+   std::fs::write(crate_root, "").unwrap();
+  }
+  let cases: [(&str, &[&str]); 22] = [
+   ("src/lib.rs", &[]),
+   ("src/main.rs", &[]),
+   ("src/api.rs", &["api"]),
+   ("src/api/mod.rs", &["api"]),
+   ("src/api/config.rs", &["api", "config"]),
+   ("src/bin/x.rs", &[]),
+   ("src/bin/x/main.rs", &[]),
+   ("src/bin/x/util.rs", &["util"]),
+   ("tests/test.rs", &[]),
+   ("tests/suite/main.rs", &[]),
+   ("tests/suite/util/mod.rs", &["util"]),
+   ("src/api/main.rs", &["api", "main"]),
+   ("examples/example1.rs", &[]),
+   // The `tests` module in `src` is not a crate root such as `tests/test.rs`.
+   ("src/tests.rs", &["tests"]),
+   ("src/tests/mod.rs", &["tests"]),
+   ("src/tests/api.rs", &["tests", "api"]),
+   ("src/examples/mod.rs", &["examples"]),
+   ("build.rs", &[]),
+   // A directory without `main.rs` is a module shared by the crates such as `mod common;` in some tests.
+   ("tests/common/mod.rs", &["common"]),
+   ("tests/common/util.rs", &["common", "util"]),
+   ("src/bin/common.rs", &[]),
+   ("src/bin/common/mod.rs", &["common"])
+  ];
+  for (source_file, expected) in cases
+  {
+   assert_eq!(
+    module_path(Some(Path::new(source_file)), &manifest_dir),
+    expected,
+    "{}",
+    source_file
+   );
+  }
+  assert!(module_path(None, &manifest_dir).is_empty());
+  std::fs::remove_dir_all(&manifest_dir).unwrap();
+ }
+
+ #[test]
+ fn inline_module_paths()
+ {
+  let file = syn::parse_file(
+   r#"
+#[derive(Tia)] struct Root { x: i32 }
+mod a { #[derive(Tia)] struct Twin { x: i32 } mod b { #[derive(tia::Tia)] struct Nested { x: i32 } } }
+mod c { #[derive(Tia)] struct Twin { y: i32 } #[derive(Debug)] struct NotTia; }
+"#
+  )
+  .unwrap();
+  let config = config::Config::default();
+  let hash = |source: &str| input_hash(&syn::parse_str(source).unwrap(), &config);
+
+  assert_eq!(inline_module_path(&file, "Root", "", &config), Ok(vec![]));
+  assert_eq!(
+   inline_module_path(&file, "Nested", "", &config),
+   Ok(vec!["a".to_string(), "b".to_string()])
+  );
+  assert_eq!(inline_module_path(&file, "NotTia", "", &config), Ok(vec![]));
+  // The same-named types are distinguished by the input hash.
+  assert_eq!(
+   inline_module_path(&file, "Twin", &hash("#[derive(Tia)] struct Twin { x: i32 }"), &config),
+   Ok(vec!["a".to_string()])
+  );
+  assert_eq!(
+   inline_module_path(&file, "Twin", &hash("#[derive(Tia)] struct Twin { y: i32 }"), &config),
+   Ok(vec!["c".to_string()])
+  );
+  // The common modules if they could not be distinguished, eg. `#[cfg(unix)] mod a` and `#[cfg(windows)] mod c`.
+  assert_eq!(
+   inline_module_path(&file, "Twin", &hash("struct Twin { z: i32 }"), &config),
+   Err(vec![])
+  );
+  let file = syn::parse_file(
+   "mod a { mod b { #[derive(Tia)] struct Twin { #[cfg(unix)] x: i32 } } mod c { #[derive(Tia)] struct Twin { #[cfg(windows)] x: i32 } } }"
+  )
+  .unwrap();
+  assert_eq!(
+   inline_module_path(&file, "Twin", &hash("#[derive(Tia)] struct Twin {}"), &config),
+   Err(vec!["a".to_string()])
+  );
+ }
+
+ /// The source file of `local_file` could be relative to the workspace root, it is the current directory in the build.
+ #[test]
+ fn relative_source_files()
+ {
+  let current_dir = std::env::current_dir().unwrap();
+  assert_eq!(
+   relative_source_file(&current_dir.join("my_crate/src/api.rs"), &current_dir.join("my_crate")),
+   Some(PathBuf::from("src/api.rs"))
+  );
+  assert_eq!(
+   relative_source_file(Path::new("my_crate/src/api.rs"), &current_dir.join("my_crate")),
+   Some(PathBuf::from("src/api.rs"))
+  );
+  assert_eq!(
+   relative_source_file(Path::new("other/src/api.rs"), &current_dir.join("my_crate")),
+   None
+  );
+ }
+
+ #[test]
+ fn resolve_out_dirs()
+ {
+  let manifest_dir = PathBuf::from("/path/to/my_crate");
+  let config = config::Config {
+   manifest_dir: manifest_dir.clone(),
+   ..Default::default()
+  };
+  assert_eq!(resolve_out_dir(&config), manifest_dir.join("src/.tia"));
+  let config = config::Config {
+   manifest_dir: manifest_dir.clone(),
+   out_dir: "generated".to_string(),
+   ..Default::default()
+  };
+  assert_eq!(resolve_out_dir(&config), manifest_dir.join("generated"));
+ }
+
+ /// `OUT_DIR` is set only if the crate has a build script such as `build.rs` of this crate.
+ #[test]
+ fn resolve_out_dir_of_build_script()
+ {
+  let config = config::Config {
+   out_dir: "$OUT_DIR/tia".to_string(),
+   ..Default::default()
+  };
+  assert_eq!(resolve_out_dir(&config), PathBuf::from(env!("OUT_DIR")).join("tia"));
+
+  std::env::remove_var("OUT_DIR");
+  let result = std::panic::catch_unwind(|| resolve_out_dir(&config));
+  std::env::set_var("OUT_DIR", env!("OUT_DIR"));
+  let message = result
+   .err()
+   .and_then(|payload| {
+    payload
+     .downcast::<String>()
+     .ok()
+   })
+   .unwrap();
+  assert!(message.contains("#TIA-PANIC-4007"), "{}", message);
+ }
+
+ #[test]
+ fn write_file_atomically()
+ {
+  let dir = std::env::temp_dir().join(format!("tia-output-{}", std::process::id()));
+  let path = dir.join("nested/Sample.rs");
+  let file = GeneratedFile {
+   path:  path.clone(),
+   owner: "src/lib.rs".to_string()
+  };
+  write_file("Sample", &file, "0123456789abcdef", "struct Sample;", &config::Config::default());
+  let source = std::fs::read_to_string(&path).unwrap();
+  assert!(source.starts_with("// tia:input-hash=0123456789abcdef\n"));
+  assert!(source.contains("struct Sample;"));
+  // No temporary files are left.
+  assert_eq!(
+   std::fs::read_dir(
+    path
+     .parent()
+     .unwrap()
+   )
+   .unwrap()
+   .count(),
+   1
+  );
+  std::fs::remove_dir_all(&dir).unwrap();
+ }
+
+ /// The owner could rewrite the file such as in a proc-macro server, but another type could not.
+ #[test]
+ fn write_file_of_another_type()
+ {
+  let dir = std::env::temp_dir().join(format!("tia-output-owner-{}", std::process::id()));
+  let file = |owner: &str| {
+   GeneratedFile {
+    path:  dir.join("Config.rs"),
+    owner: owner.to_string()
+   }
+  };
+  let config = config::Config::default();
+  write_file("Config", &file("src/lib.rs"), "0123456789abcdef", "struct Config;", &config);
+  write_file("Config", &file("src/lib.rs"), "fedcba9876543210", "struct Config{x:i32}", &config);
+  let result = std::panic::catch_unwind(|| write_file("Config", &file("src/tests/mod.rs"), "0123456789abcdef", "struct Config;", &config));
+  std::fs::remove_dir_all(&dir).unwrap();
+  let message = result
+   .err()
+   .and_then(|payload| {
+    payload
+     .downcast::<String>()
+     .ok()
+   })
+   .unwrap();
+  assert!(message.contains("#TIA-PANIC-4012"), "{}", message);
+ }
+
+ #[test]
+ fn input_hashes()
+ {
+  let config = config::Config::default();
+  let hash = |source: &str| input_hash(&syn::parse_str(source).unwrap(), &config);
+  let base = hash("#[derive(Tia)]\n#[tia(rg)]\nstruct Sample { x: i32 }");
+  assert_eq!(base.len(), 16);
+  // The spacing, the doc comments and the derives are not the input.
+  assert_eq!(hash("/// Doc.\n#[derive(Debug, Tia)] #[tia(rg)] struct Sample{x:i32}"), base);
+  // The directives and the fields are the input.
+  assert_ne!(hash("#[derive(Tia)]\n#[tia(rg, s)]\nstruct Sample { x: i32 }"), base);
+  assert_ne!(hash("#[derive(Tia)]\n#[tia(rg)]\nstruct Sample { x: i64 }"), base);
+ }
+
+ #[test]
+ fn include_states()
+ {
+  let dir = std::env::temp_dir().join(format!("tia-include-state-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let path = dir.join("Sample.rs");
+  assert_eq!(read_input_hash(&path), None);
+  assert_eq!(include_state(&path, "0123456789abcdef"), IncludeState::NotFound);
+
+  std::fs::write(&path, "impl Sample {}\n").unwrap();
+  assert_eq!(read_input_hash(&path), None);
+  assert_eq!(include_state(&path, "0123456789abcdef"), IncludeState::NoInputHash);
+
+  std::fs::write(&path, "// tia:input-hash=0123456789abcdef\nimpl Sample {}\n").unwrap();
+  assert_eq!(read_input_hash(&path).as_deref(), Some("0123456789abcdef"));
+  assert_eq!(include_state(&path, "0123456789abcdef"), IncludeState::UpToDate);
+  assert_eq!(
+   include_state(&path, "fedcba9876543210"),
+   IncludeState::Stale("0123456789abcdef".to_string())
+  );
+  std::fs::remove_dir_all(&dir).unwrap();
+ }
+
+ #[test]
+ fn pretty_prints()
+ {
+  assert_eq!(
+   pretty_print("impl Sample{pub fn get_x(&self)->i32{self.x}}").unwrap(),
+   "impl Sample {\n    pub fn get_x(&self) -> i32 {\n        self.x\n    }\n}\n"
+  );
+  assert!(pretty_print("impl Sample{pub fn get_x(&self)->").is_err());
+ }
+
+ /// `rustfmt.toml` of the package or the workspace is found from `CARGO_MANIFEST_DIR`, not from `out_dir`.
+ #[test]
+ fn find_rustfmt_configs()
+ {
+  let dir = std::env::temp_dir().join(format!("tia-rustfmt-config-{}", std::process::id()));
+  let package = dir.join("workspace/package");
+  std::fs::create_dir_all(&package).unwrap();
+  assert_eq!(find_rustfmt_config(&package), None);
+
+  std::fs::write(dir.join("workspace/.rustfmt.toml"), "hard_tabs = true\n").unwrap();
+  assert_eq!(find_rustfmt_config(&package), Some(dir.join("workspace/.rustfmt.toml")));
+
+  std::fs::write(package.join("rustfmt.toml"), "edition = \"2021\"\n").unwrap();
+  assert_eq!(find_rustfmt_config(&package), Some(package.join("rustfmt.toml")));
+  std::fs::remove_dir_all(&dir).unwrap();
+ }
+
+ /// The raw source is written if the formatter failed, and the log shows `<NG>` with the reason.
+ #[test]
+ fn pretty_print_failure()
+ {
+  let dir = std::env::temp_dir().join(format!("tia-output-ng-{}", std::process::id()));
+  let path = dir.join("Broken.rs");
+  let config = config::Config {
+   rustfmt: true,
+   formatter: Some(config::Formatter::Prettyplease),
+   ..Default::default()
+  };
+  let file = GeneratedFile {
+   path:  path.clone(),
+   owner: "src/lib.rs".to_string()
+  };
+  write_file("Broken", &file, "0123456789abcdef", "impl Broken{fn", &config);
+  assert!(std::fs::read_to_string(&path)
+   .unwrap()
+   .ends_with("\nimpl Broken{fn"));
+  std::fs::remove_dir_all(&dir).unwrap();
+
+  let result = Some(("prettyplease", pretty_print("impl Broken{fn").map(|_| ())));
+  let message = log_message("Broken", &path, &result);
+  assert!(message.contains("; prettyplease <NG> "), "{}", message);
+  assert!(log_message("Sample", &path, &Some(("rustfmt", Ok(())))).contains("; rustfmt <OK> ]"));
+  assert!(log_message("Sample", &path, &None).starts_with("[proc-macro:tia +file Target: Sample"));
+ }
+}
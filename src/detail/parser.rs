@@ -1,12 +1,43 @@
+mod call_directive;
+mod delegate;
+mod flatten;
+mod for_type;
+mod preset;
 mod stringify;
+mod trait_definition;
+mod trait_symbol;
+mod virtual_accessor;
 
 use super::*;
+use quote::ToTokens;
 use std::collections::HashSet;
-use syn::parse::Parser;
+use syn::parse::{
+ Parse,
+ ParseStream
+};
 
 const TIA: &str = "tia";
 const COLON_COLON_SEPARATOR: &str = "::";
 const COMMA: &str = ",";
+const NAMING: &str = "naming";
+const DEFINE_TRAIT: &str = "define_trait";
+const DELEGATE: &str = "delegate";
+const DELEGATE_FN: &str = "delegate_fn";
+const ON_CHANGE: &str = "on_change";
+const ON_CHANGE_IF_CHANGED: &str = "on_change_if_changed";
+const DIRTY: &str = "dirty";
+const REFLECT: &str = "reflect";
+const VISITOR: &str = "visitor";
+const LENS: &str = "lens";
+const FLATTEN: &str = "flatten";
+const MERGE: &str = "merge";
+const FOR_TYPE: &str = "for_type";
+const SKIP: &str = "skip";
+const EXCLUDE: &str = "exclude";
+const PRESET: &str = "preset";
+const PRINT: &str = "print";
+const EMIT_FILE: &str = "emit_file";
+const DISABLED: &str = "disabled";
 
 #[derive(Debug)]
 enum TiaParamToken
@@ -17,41 +48,222 @@ enum TiaParamToken
  {
   key:   String,
   value: FieldSymbolPolicy
+ },
+ NamingStyle(NamingStyle),
+ TraitDefinition
+ {
+  trait_symbol: TraitSymbol,
+  definition:   TraitDefinition
+ },
+ Delegations(Vec<String>),
+ OnChange(OnChange),
+ Dirty(FieldSymbol),
+ Reflect,
+ Visitor(VisitorPolicy),
+ Lens(String),
+ /// `merge` for the current trait, or `merge="Trait"`
+ Merge(Option<TraitSymbol>),
+ VirtualAccessor
+ {
+  accessor:   Accessor,
+  field_type: FieldType,
+  expr:       String
+ },
+ Accessor(Accessor),
+ /// `for_type(bool="g", "Vec<_>"="rmg, s")` => `[("bool", [g]), ("Vec<_>", [rmg, s])]`
+ ForType(Vec<(FieldType, TiaParamTokenStream)>),
+ /// `skip` => `[]`, `skip(g, "Trait")` => `[Accessor("g"), Trait("Trait")]`
+ Skip(Vec<SkipTarget>),
+ Exclude(Vec<FieldSymbol>),
+ /// `preset="pod"`, it will be expanded by `preset::expand`.
+ Preset(String),
+ /// `print`, `emit_file` or `disabled`
+ Output(String),
+ CallDirective
+ {
+  key:       String,
+  arguments: Vec<(String, String)>
+ }
+}
+
+/// An element of `skip(...)`.
+#[derive(Debug)]
+enum SkipTarget
+{
+ /// `g` => the inherited getter of the current trait
+ Accessor(Accessor),
+ /// `"Trait"` => all of the inherited accessors of the trait
+ Trait(TraitSymbol)
+}
+
+/// Where the tia directives are written.
+enum Scope<'a>
+{
+ /// `#[tia(...)]` of struct|enum|union
+ Root(&'a mut TypeParams),
+ /// `#[tia(...)]` of a field
+ Field(&'a mut TargetField)
+}
+
+/// A comma separated element of `#[tia(...)]`.
+enum TiaParamSyntax
+{
+ /// `trait = crate::api::Getter<i32>`
+ Trait(syn::Path),
+ /// `virtual(g="area", ty="f64", expr="self.w * self.h")`, `virtual` is a reserved keyword thus it is not an `Expr`.
+ Virtual(syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>),
+ /// `rg`, `"Trait"`, `g="prefix"`, `Trait: g` and the others.
+ Expr(syn::Expr)
+}
+
+impl Parse for TiaParamSyntax
+{
+ fn parse(input: ParseStream) -> syn::Result<Self>
+ {
+  if input.peek(syn::Token![trait])
+  {
+   input.parse::<syn::Token![trait]>()?;
+   input.parse::<syn::Token![=]>()?;
+   return Ok(TiaParamSyntax::Trait(input.parse()?));
+  }
+  if input.peek(syn::Token![virtual])
+  {
+   input.parse::<syn::Token![virtual]>()?;
+   let content;
+   syn::parenthesized!(content in input);
+   return Ok(TiaParamSyntax::Virtual(content.parse_terminated(syn::Expr::parse)?));
+  }
+  Ok(TiaParamSyntax::Expr(input.parse()?))
  }
 }
 
 type TiaParamTokenStream = Vec<TiaParamToken>;
 type TraitToAccessors = HashMap<TraitSymbol, HashSet<Accessor>>;
 
-pub fn parse(i: syn::DeriveInput) -> Tia
+pub fn parse(i: syn::DeriveInput) -> syn::Result<Tia> { parse_with(i, config::config()) }
+
+/// `parse` with the config, eg. a config of a test.
+pub fn parse_with(i: syn::DeriveInput, config: std::sync::Arc<config::Config>) -> syn::Result<Tia>
 {
  let target_type_symbol = i
   .ident
   .to_string();
  let target_type = get_target_type(&i);
+ let target_visibility = format!(
+  "{} ",
+  i.vis
+   .to_token_stream()
+ );
+
+ let (root_ta, type_params) = parse_root(&i, &config)?;
+ let (target_fields, mut trait_to_field_accessors) = parse_fields(&i, &root_ta, &type_params, &config)?;
+
+ // A trait might have only the virtual accessors.
+ for virtual_accessor in &type_params.virtual_accessors
+ {
+  trait_to_field_accessors
+   .entry(
+    virtual_accessor
+     .trait_symbol
+     .clone()
+   )
+   .or_default();
+ }
+
+ if let Some(excluded) = type_params
+  .excluded
+  .iter()
+  .find(|&excluded| {
+   !target_fields
+    .iter()
+    .any(|target_field| &target_field.field_symbol == excluded)
+  })
+ {
+  panic!(
+   "tia syntax error: `exclude=\"{}\"` requires the field `{}` in `{}`, check the field symbols please. #TIA-PANIC-1055",
+   excluded, excluded, target_type_symbol
+  )
+ }
 
- let root_ta = parse_root(&i);
- let trait_to_field_accessors = parse_fields(&i, &root_ta);
+ if let Some(dirty) = &type_params.dirty
+ {
+  if !target_fields
+   .iter()
+   .any(|target_field| &target_field.field_symbol == dirty)
+  {
+   panic!(
+    "tia syntax error: `dirty` requires a field such as `{}: {}Dirty`, add the field please. #TIA-PANIC-1035",
+    dirty, target_type_symbol
+   )
+  }
+ }
 
- Tia {
+ Ok(Tia {
+  input_hash: output::input_hash(&i, &config),
   target_type_symbol,
   target_type,
-  trait_to_field_accessors
+  target_visibility,
+  type_params,
+  target_fields,
+  trait_to_field_accessors,
+  config
+ })
+}
+
+/// `impl crate::Trait for MyStruct { ... }` => ( `MyStruct`, `crate :: Trait` )
+pub fn parse_merge_target(item_impl: &syn::ItemImpl) -> syn::Result<(TargetTypeSymbol, TraitSymbol)>
+{
+ let target_type_symbol = match item_impl
+  .self_ty
+  .as_ref()
+ {
+  syn::Type::Path(type_path)
+   if type_path
+    .qself
+    .is_none() =>
+  {
+   type_path
+    .path
+    .segments
+    .last()
+    .map(|segment| {
+     segment
+      .ident
+      .to_string()
+    })
+  },
+  _ => None
  }
+ .ok_or_else(|| {
+  syn::Error::new_spanned(
+   &item_impl.self_ty,
+   "tia syntax error; `#[tia::impl_accessors]` requires the type which has `#[derive(Tia)]`. #TIA-ERROR-1046"
+  )
+ })?;
+ let trait_symbol = item_impl
+  .trait_
+  .as_ref()
+  .map(|(_, path, _)| trait_symbol::from_path(path))
+  .unwrap_or_default();
+ Ok((target_type_symbol, trait_symbol))
 }
 
-fn parse_root(i: &syn::DeriveInput) -> TraitToAccessors
+fn parse_root(i: &syn::DeriveInput, config: &config::Config) -> syn::Result<(TraitToAccessors, TypeParams)>
 {
- match find_tia_attribute(&i.attrs)
+ let mut type_params = TypeParams {
+  naming_style: config.naming,
+  ..Default::default()
+ };
+ let root_ta = match find_tia_attribute(&i.attrs)
  {
   Some(attribute) =>
   {
-   let root_tia_params = parse_tia_params(&attribute);
-   let root_ta = translate_tia_params(root_tia_params);
-   root_ta
+   let root_tia_params = parse_tia_params(attribute)?;
+   translate_tia_params(root_tia_params, Scope::Root(&mut type_params), config)
   },
   None => TraitToAccessors::default()
- }
+ };
+ Ok((root_ta, type_params))
 }
 
 fn get_field_symbol(field: &syn::Field) -> String
@@ -73,7 +285,7 @@ fn get_target_type(i: &syn::DeriveInput) -> TargetType
  }
 }
 
-fn get_fields(data: &syn::Data) -> syn::punctuated::Iter<syn::Field>
+fn get_fields(data: &syn::Data) -> syn::punctuated::Iter<'_, syn::Field>
 {
  match data
  {
@@ -92,8 +304,14 @@ fn get_fields(data: &syn::Data) -> syn::punctuated::Iter<syn::Field>
  }
 }
 
-fn parse_fields(i: &syn::DeriveInput, root_ta: &TraitToAccessors) -> TraitToFieldAccessors
+fn parse_fields(
+ i: &syn::DeriveInput,
+ root_ta: &TraitToAccessors,
+ type_params: &TypeParams,
+ config: &config::Config
+) -> syn::Result<(Vec<TargetField>, TraitToFieldAccessors)>
 {
+ let mut target_fields = vec![];
  let mut ttfa = TraitToFieldAccessors::default();
 
  let fields = get_fields(&i.data);
@@ -102,179 +320,477 @@ fn parse_fields(i: &syn::DeriveInput, root_ta: &TraitToAccessors) -> TraitToFiel
  {
   let field_symbol = get_field_symbol(field);
   let field_type = stringify::decode_type(&field.ty);
-  let ta = match find_tia_attribute(&field.attrs)
+  let mut target_field = TargetField {
+   field_symbol: field_symbol.clone(),
+   field_type: field_type.clone(),
+   visibility: format!(
+    "{} ",
+    field
+     .vis
+     .to_token_stream()
+   ),
+   ..Default::default()
+  };
+  let field_ta = match find_tia_attribute(&field.attrs)
   {
    Some(attribute) =>
    {
-    let field_tia_token_stream = parse_tia_params(attribute);
-    let field_ta = translate_tia_params(field_tia_token_stream);
-    let mut ta = root_ta.clone();
-    for (t, aa) in field_ta
+    let field_tia_token_stream = parse_tia_params(attribute)?;
+    translate_tia_params(field_tia_token_stream, Scope::Field(&mut target_field), config)
+   },
+   None => TraitToAccessors::default()
+  };
+  target_field.skip |= type_params
+   .excluded
+   .contains(&field_symbol);
+  // root < for_type < field, the inherited root and for_type are ignored if the field is skipped.
+  let mut ta = TraitToAccessors::default();
+  if !target_field.skip
+  {
+   ta = root_ta.clone();
+   for (t, a) in type_params
+    .for_types
+    .iter()
+    .filter(|for_type| for_type::matches(&for_type.pattern, &field.ty))
+    .flat_map(|for_type| &for_type.accessors)
+   {
+    ta
+     .entry(t.clone())
+     .or_default()
+     .replace(a.clone());
+   }
+   for (t, a) in &target_field.skipped
+   {
+    match a
     {
-     let ta_aa = ta
-      .entry(t)
-      .or_default();
-     for a in aa
+     Some(a) =>
+     {
+      if let Some(aa) = ta.get_mut(t)
+      {
+       aa.remove(a);
+      }
+     },
+     None =>
      {
-      ta_aa.replace(a);
+      ta.remove(t);
      }
     }
-    ta
-   },
-   None => root_ta.clone()
-  };
-  for (t, a) in ta
+   }
+  }
+  for (t, aa) in field_ta
   {
-   ttfa
+   let ta_aa = ta
     .entry(t)
+    .or_default();
+   for a in aa
+   {
+    ta_aa.replace(a);
+   }
+  }
+  let on_change = target_field
+   .on_change
+   .clone()
+   .or_else(|| {
+    type_params
+     .on_change
+     .clone()
+     .filter(|_| !target_field.skip)
+   });
+  for (t, a) in ta
+  {
+   let field_params = ttfa
+    .entry(t.clone())
     .or_default()
     .insert(field_symbol.clone(), FieldParams {
      field_type: field_type.clone(),
-     accessors:  a
+     accessors:  a,
+     on_change:  on_change.clone(),
+     path:       None
     });
+   if let Some(FieldParams {
+    path: Some(path), ..
+   }) = field_params
+   {
+    panic_flatten_clash(&field_symbol, &path, &field_symbol, &t)
+   }
   }
+  for flatten in &target_field.flattens
+  {
+   let flatten_symbol = flatten
+    .path
+    .rsplit('.')
+    .next()
+    .unwrap_or_default()
+    .to_string();
+   let path = format!("{}.{}", field_symbol, flatten.path);
+   let field_params = ttfa
+    .entry(
+     flatten
+      .trait_symbol
+      .clone()
+    )
+    .or_default()
+    .entry(flatten_symbol.clone())
+    .or_insert_with(|| {
+     FieldParams {
+      field_type: flatten
+       .field_type
+       .clone(),
+      on_change: on_change.clone(),
+      path: Some(path.clone()),
+      ..Default::default()
+     }
+    });
+   // The accessors of a flatten member are merged only into the same member, eg. `flatten(rg="x: f32", s="x: f32")`.
+   if field_params
+    .path
+    .as_ref()
+    != Some(&path)
+   {
+    let other = field_params
+     .path
+     .clone()
+     .unwrap_or_else(|| flatten_symbol.clone());
+    panic_flatten_clash(&flatten_symbol, &path, &other, &flatten.trait_symbol)
+   }
+   field_params
+    .accessors
+    .replace(
+     flatten
+      .accessor
+      .clone()
+    );
+  }
+  target_fields.push(target_field);
  }
 
- ttfa
+ Ok((target_fields, ttfa))
+}
+
+/// `x: i32` and `flatten(rg="x: i32")` of `pos: Position` => the accessors of them would be same, eg. `get_x`.
+fn panic_flatten_clash(flatten_symbol: &str, path: &str, other: &str, trait_symbol: &TraitSymbol) -> !
+{
+ panic!(
+  r#"tia syntax error: `flatten(...="{}: ...")` of `{}` clashes with `{}` in {}, the accessors of them would be same. Move one of them to another trait please. #TIA-PANIC-1061"#,
+  flatten_symbol,
+  path,
+  other,
+  match trait_symbol.is_empty()
+  {
+   true => "the inherent `impl`".to_string(),
+   false => format!("the trait `{}`", trait_symbol)
+  }
+ )
 }
 
-fn translate_tia_params(tia_param_token_stream: TiaParamTokenStream) -> TraitToAccessors
+fn translate_tia_params(tia_param_token_stream: TiaParamTokenStream, mut scope: Scope, config: &config::Config) -> TraitToAccessors
 {
  let mut ta = TraitToAccessors::default();
 
  let mut current_trait_symbol = String::default();
- for tia_param_token in tia_param_token_stream
+ for tia_param_token in preset::expand(tia_param_token_stream, config)
  {
   match tia_param_token
   {
    TiaParamToken::TraitSymbol(trait_symbol) => current_trait_symbol = trait_symbol,
    TiaParamToken::DefaultDirective(ref key) =>
    {
-    let accessor = match &key[..]
+    let accessor = translate_accessor(key, FieldSymbolPolicy::Default).unwrap_or_else(|| {
+     panic!(
+      "tia syntax error: Check around of directives, maybe you wrote an unsupported keyword or typo such as `&g`, `rms` or `brabrabra` \
+       #TIA-PANIC-1014."
+     )
+    });
+    ta
+     .entry(current_trait_symbol.clone())
+     .or_default()
+     .replace(accessor);
+   },
+   TiaParamToken::CustomDirective {
+    key,
+    value
+   } =>
+   {
+    let accessor = translate_accessor(&key, value).unwrap_or_else(|| {
+     panic!(
+      r#"tia syntax error: Check around of directive, maybe you wrote an unsupported keyword or typo such as `&g="bad-symbol"`, `rms='bad_quote'` or `brabrabra`. #TIA-PANIC-1013"#
+     )
+    });
+    ta
+     .entry(current_trait_symbol.clone())
+     .or_default()
+     .replace(accessor);
+   },
+   TiaParamToken::NamingStyle(naming_style) =>
+   {
+    match scope
     {
-     "gm" =>
+     Scope::Root(ref mut type_params) => type_params.naming_style = naming_style,
+     Scope::Field(_) =>
      {
-      Accessor::Getter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: GetterParameterTypePolicy::Move
-      }
-     },
-     "g" =>
+      panic!(r#"tia syntax error: `naming="..."` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1018"#)
+     }
+    }
+   },
+   TiaParamToken::TraitDefinition {
+    trait_symbol,
+    definition
+   } =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) =>
      {
-      Accessor::Getter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: GetterParameterTypePolicy::Value
-      }
+      type_params
+       .trait_definitions
+       .insert(trait_symbol.clone(), definition);
      },
-     "rg" =>
+     Scope::Field(_) =>
      {
-      Accessor::Getter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: GetterParameterTypePolicy::Ref
-      }
-     },
-     "rmg" =>
+      panic!(
+       r#"tia syntax error: `define_trait="..."` is a struct|enum|union-level directive, it could not be use for a field. Use `"TraitSymbol"` for a field instead. #TIA-PANIC-1020"#
+      )
+     }
+    }
+    current_trait_symbol = trait_symbol;
+   },
+   TiaParamToken::Delegations(mut delegations) =>
+   {
+    match scope
+    {
+     Scope::Field(ref mut target_field) =>
      {
-      Accessor::Getter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: GetterParameterTypePolicy::RefMut
-      }
+      target_field
+       .delegations
+       .append(&mut delegations)
      },
-     "s" =>
+     Scope::Root(_) =>
      {
-      Accessor::Setter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: SetterParameterTypePolicy::Value
-      }
-     },
-     "rsc" =>
+      panic!(
+       r#"tia syntax error: `delegate="..."` is a field-level directive, it could not be use for a struct|enum|union. #TIA-PANIC-1023"#
+      )
+     }
+    }
+   },
+   TiaParamToken::OnChange(on_change) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) => type_params.on_change = Some(on_change),
+     Scope::Field(ref mut target_field) => target_field.on_change = Some(on_change)
+    }
+   },
+   TiaParamToken::Dirty(field_symbol) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) => type_params.dirty = Some(field_symbol),
+     Scope::Field(_) =>
      {
-      Accessor::Setter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: SetterParameterTypePolicy::RefClone
-      }
-     },
-     "rsi" =>
+      panic!(r#"tia syntax error: `dirty` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1034"#)
+     }
+    }
+   },
+   TiaParamToken::Reflect =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) => type_params.reflect = true,
+     Scope::Field(_) =>
      {
-      Accessor::Setter {
-       fsp: FieldSymbolPolicy::Default,
-       ptp: SetterParameterTypePolicy::Into
+      panic!(r#"tia syntax error: `reflect` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1036"#)
+     }
+    }
+   },
+   TiaParamToken::Output(key) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) =>
+     {
+      match &key[..]
+      {
+       PRINT => type_params.print = true,
+       EMIT_FILE => type_params.emit_file = true,
+       _ => type_params.disabled = true
       }
      },
-     _ =>
+     Scope::Field(_) =>
      {
       panic!(
-       "tia syntax error: Check around of directives, maybe you wrote an unsupported keyword or typo such as `&g`, `rms` or `brabrabra` \
-        #TIA-PANIC-1014."
+       r#"tia syntax error: `{}` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1060"#,
+       key
       )
      }
-    };
-    ta
-     .entry(current_trait_symbol.clone())
-     .or_default()
-     .replace(accessor);
+    }
    },
-   TiaParamToken::CustomDirective {
-    key,
-    value
+   TiaParamToken::Visitor(visitor_policy) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) => type_params.visitor = Some(visitor_policy),
+     Scope::Field(_) =>
+     {
+      panic!(r#"tia syntax error: `visitor` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1037"#)
+     }
+    }
+   },
+   TiaParamToken::Lens(lens_module) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) => type_params.lens = Some(lens_module),
+     Scope::Field(_) =>
+     {
+      panic!(r#"tia syntax error: `lens` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1038"#)
+     }
+    }
+   },
+   TiaParamToken::VirtualAccessor {
+    accessor,
+    field_type,
+    expr
    } =>
    {
-    let fsp = value;
-    let accessor = match &key[..]
+    match scope
     {
-     "g" =>
+     Scope::Root(ref mut type_params) =>
      {
-      Accessor::Getter {
-       fsp,
-       ptp: GetterParameterTypePolicy::Value
-      }
+      type_params
+       .virtual_accessors
+       .push(VirtualAccessor {
+        trait_symbol: current_trait_symbol.clone(),
+        accessor,
+        field_type,
+        expr
+       })
      },
-     "rg" =>
+     Scope::Field(_) =>
      {
-      Accessor::Getter {
-       fsp,
-       ptp: GetterParameterTypePolicy::Ref
-      }
-     },
-     "rmg" =>
+      panic!(r#"tia syntax error: `virtual(...)` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1042"#)
+     }
+    }
+   },
+   TiaParamToken::Merge(trait_symbol) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) =>
      {
-      Accessor::Getter {
-       fsp,
-       ptp: GetterParameterTypePolicy::RefMut
-      }
+      type_params
+       .merged_traits
+       .insert(trait_symbol.unwrap_or_else(|| current_trait_symbol.clone()));
      },
-     "s" =>
+     Scope::Field(_) =>
      {
-      Accessor::Setter {
-       fsp,
-       ptp: SetterParameterTypePolicy::Value
-      }
+      panic!(
+       r#"tia syntax error: `merge` is a struct|enum|union-level directive, it could not be use for a field. Write `#[tia("Trait", merge)]` for the struct|enum|union instead. #TIA-PANIC-1045"#
+      )
+     }
+    }
+   },
+   TiaParamToken::ForType(rules) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) =>
+     {
+      let mut for_types = for_type::translate(rules, &current_trait_symbol, config);
+      type_params
+       .for_types
+       .append(&mut for_types)
      },
-     "rsc" =>
+     Scope::Field(_) =>
      {
-      Accessor::Setter {
-       fsp,
-       ptp: SetterParameterTypePolicy::RefClone
-      }
+      panic!(
+       r#"tia syntax error: `for_type(...)` is a struct|enum|union-level directive, it could not be use for a field. #TIA-PANIC-1051"#
+      )
+     }
+    }
+   },
+   TiaParamToken::Skip(skip_targets) =>
+   {
+    match scope
+    {
+     Scope::Field(ref mut target_field) if skip_targets.is_empty() => target_field.skip = true,
+     Scope::Field(ref mut target_field) =>
+     {
+      target_field
+       .skipped
+       .extend(
+        skip_targets
+         .into_iter()
+         .map(|skip_target| {
+          match skip_target
+          {
+           SkipTarget::Accessor(accessor) => (current_trait_symbol.clone(), Some(accessor)),
+           SkipTarget::Trait(trait_symbol) => (trait_symbol, None)
+          }
+         })
+       )
      },
-     "rsi" =>
+     Scope::Root(_) =>
      {
-      Accessor::Setter {
-       fsp,
-       ptp: SetterParameterTypePolicy::Into
-      }
+      panic!(
+       r#"tia syntax error: `skip` is a field-level directive, it could not be use for a struct|enum|union. Use `exclude="..."` instead. #TIA-PANIC-1052"#
+      )
+     }
+    }
+   },
+   TiaParamToken::Exclude(mut field_symbols) =>
+   {
+    match scope
+    {
+     Scope::Root(ref mut type_params) =>
+     {
+      type_params
+       .excluded
+       .append(&mut field_symbols)
      },
-     _ =>
+     Scope::Field(_) =>
      {
       panic!(
-       r#"tia syntax error: Check around of directive, maybe you wrote an unsupported keyword or typo such as `&g="bad-symbol"`, `rms='bad_quote'` or `brabrabra`. #TIA-PANIC-1013"#
+       r#"tia syntax error: `exclude="..."` is a struct|enum|union-level directive, it could not be use for a field. Use `skip` instead. #TIA-PANIC-1053"#
       )
      }
-    };
+    }
+   },
+   TiaParamToken::Preset(_) => unreachable!("tia implementation bug: the presets should be expanded. #TIA-PANIC-1059"),
+   TiaParamToken::Accessor(accessor) =>
+   {
     ta
      .entry(current_trait_symbol.clone())
      .or_default()
      .replace(accessor);
+   },
+   TiaParamToken::CallDirective {
+    key,
+    arguments
+   } if key == FLATTEN =>
+   {
+    match scope
+    {
+     Scope::Field(ref mut target_field) =>
+     {
+      target_field
+       .flattens
+       .append(&mut flatten::parse_flattens(arguments, &current_trait_symbol))
+     },
+     Scope::Root(_) =>
+     {
+      panic!(r#"tia syntax error: `flatten(...)` is a field-level directive, it could not be use for a struct|enum|union. #TIA-PANIC-1041"#)
+     }
+    }
+   },
+   TiaParamToken::CallDirective {
+    key,
+    arguments
+   } =>
+   {
+    ta
+     .entry(current_trait_symbol.clone())
+     .or_default()
+     .replace(call_directive::translate(&key, arguments));
    }
   }
  }
@@ -282,6 +798,108 @@ fn translate_tia_params(tia_param_token_stream: TiaParamTokenStream) -> TraitToA
  ta
 }
 
+/// `key` such as `rg` with `fsp` => `Some(Accessor)`, or `None` if `key` is not an accessor directive.
+fn translate_accessor(key: &str, fsp: FieldSymbolPolicy) -> Option<Accessor>
+{
+ let accessor = match key
+ {
+  "gm" =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::Move
+   }
+  },
+  "g" =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::Value
+   }
+  },
+  "rg" =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::Ref
+   }
+  },
+  "rmg" =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::RefMut
+   }
+  },
+  "rgc" =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::Clone
+   }
+  },
+  // The target type will be set by `gi(ty=...)`.
+  "gi" =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::Into(FieldType::new())
+   }
+  },
+  "s" =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::Value,
+    svp: None
+   }
+  },
+  "rsc" =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::RefClone,
+    svp: None
+   }
+  },
+  "rsi" =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::Into,
+    svp: None
+   }
+  },
+  "rsti" =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::TryInto,
+    svp: None
+   }
+  },
+  // The borrowed type could be set by `rsa(ty=...)`.
+  "rsa" =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::AsRef(None),
+    svp: None
+   }
+  },
+  "rsp" =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::Parse,
+    svp: None
+   }
+  },
+  _ => return None
+ };
+ Some(accessor)
+}
+
 fn find_tia_attribute(attributes: &[syn::Attribute]) -> Option<&syn::Attribute>
 {
  attributes
@@ -290,54 +908,43 @@ fn find_tia_attribute(attributes: &[syn::Attribute]) -> Option<&syn::Attribute>
    let attribute_path_segments = &a
     .path
     .segments;
-   match attribute_path_segments.first()
-   {
-    Some(s) if s.ident == TIA => true,
-    _ => false
-   }
+   matches!(attribute_path_segments.first(), Some(s) if s.ident == TIA)
   })
 }
 
-fn parse_tia_params(attribute: &syn::Attribute) -> TiaParamTokenStream
+fn parse_tia_params(attribute: &syn::Attribute) -> syn::Result<TiaParamTokenStream>
 {
- let parser = syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated;
- let tokens = attribute
+ // It is not an error that the tia attribute does not have any parameters.
+ if attribute
   .tokens
-  .clone();
- let tokens = parser
-  .parse(tokens.into())
-  .expect("tia syntax error: syn parse was failed. #TIA-PANIC-1012");
-
- match tokens.first()
+  .is_empty()
  {
-  // #[tia(x,y,...)] pattern
-  Some(syn::Expr::Tuple(root)) =>
-  {
-   let mut tia_params: TiaParamTokenStream = vec![];
-   for expr in root
-    .elems
-    .iter()
-   {
-    tia_params.append(&mut parse_tia_param_syn_expr(expr));
-   }
-   tia_params
-  },
+  return Ok(vec![]);
+ }
+
+ let params = attribute.parse_args_with(syn::punctuated::Punctuated::<TiaParamSyntax, syn::Token![,]>::parse_terminated)?;
+ translate_tia_param_syntaxes(params)
+}
 
-  // #[tia(x)] or #[tia(t:x)] pattern
-  Some(syn::Expr::Paren(root)) =>
+fn translate_tia_param_syntaxes(params: impl IntoIterator<Item = TiaParamSyntax>) -> syn::Result<TiaParamTokenStream>
+{
+ let mut tia_params: TiaParamTokenStream = vec![];
+ for param in params
+ {
+  match param
   {
-   parse_tia_param_syn_expr(
-    root
-     .expr
-     .as_ref()
-   )
-  },
-  // It is not an error that the tia attribute does not exists.
-  _ => vec![]
+   // #[tia(trait = crate::Trait<T>, ...)] pattern
+   TiaParamSyntax::Trait(path) => tia_params.push(TiaParamToken::TraitSymbol(trait_symbol::from_path(&path))),
+   // #[tia(virtual(g="area", ty="f64", expr="..."), ...)] pattern
+   TiaParamSyntax::Virtual(args) => tia_params.push(virtual_accessor::parse(call_directive::parse_arguments(&args))),
+   // #[tia(x, "Trait", t: x, x="y", ...)] pattern
+   TiaParamSyntax::Expr(expr) => tia_params.append(&mut parse_tia_param_syn_expr(&expr)?)
+  }
  }
+ Ok(tia_params)
 }
 
-fn parse_tia_param_syn_expr(expr: &syn::Expr) -> TiaParamTokenStream
+fn parse_tia_param_syn_expr(expr: &syn::Expr) -> syn::Result<TiaParamTokenStream>
 {
  let mut tia_params: TiaParamTokenStream = vec![];
  match expr
@@ -346,13 +953,20 @@ fn parse_tia_param_syn_expr(expr: &syn::Expr) -> TiaParamTokenStream
   {
    if let syn::Lit::Str(a) = &e_lit.lit
    {
-    let trait_symbol = TiaParamToken::TraitSymbol(a.value());
+    let trait_symbol = TiaParamToken::TraitSymbol(trait_symbol::from_lit_str(a)?);
     tia_params.push(trait_symbol)
    }
   },
   syn::Expr::Path(e_path) => tia_params.push(parse_tia_param_syn_expr_path(e_path)),
-  syn::Expr::Assign(e_assign) => tia_params.push(parse_tia_param_syn_expr_assign(e_assign)),
-  syn::Expr::AssignOp(e_assign_op) => tia_params.push(parse_tia_param_syn_expr_assign_op(e_assign_op)),
+  syn::Expr::Assign(e_assign) => tia_params.push(check_custom_directive(parse_tia_param_syn_expr_assign(e_assign), &e_assign.right)?),
+  syn::Expr::AssignOp(e_assign_op) =>
+  {
+   tia_params.push(check_custom_directive(
+    parse_tia_param_syn_expr_assign_op(e_assign_op),
+    &e_assign_op.right
+   )?)
+  },
+  syn::Expr::Call(e_call) => tia_params.push(call_directive::parse(e_call)),
   syn::Expr::Type(e_type) =>
   {
    let (p0, p1) = parse_tia_param_syn_expr_type(e_type);
@@ -365,7 +979,48 @@ fn parse_tia_param_syn_expr(expr: &syn::Expr) -> TiaParamTokenStream
    panic!(r#"tia syntax error; Check #[tia(...)] (tia proc-macro pattern) of struct|enum|union scope, maybe. #TIA-PANIC-1011"#)
   }
  };
- tia_params
+ Ok(tia_params)
+}
+
+/// `g*="as"` => an error at `"as"`, the accessor symbol of a custom directive should be an identifier.
+fn check_custom_directive(token: TiaParamToken, value: &syn::Expr) -> syn::Result<TiaParamToken>
+{
+ match &token
+ {
+  TiaParamToken::CustomDirective {
+   key,
+   value: fsp
+  } =>
+  {
+   check_field_symbol_policy(fsp).map_err(|symbol| {
+    syn::Error::new_spanned(
+     value,
+     format!(
+      "tia syntax error; `{}` of `{}` is not an identifier, it could not be an accessor symbol. #TIA-ERROR-1065",
+      symbol, key
+     )
+    )
+   })?;
+   Ok(token)
+  },
+  _ => Ok(token)
+ }
+}
+
+/// `Err` with the accessor symbol if the symbol of `fsp` is not an identifier, eg. `fullname="as"` or `prefix="1"`.
+/// The field symbol is an identifier, thus a sample field symbol is used for `Prefix` and `Suffix`.
+pub fn check_field_symbol_policy(fsp: &FieldSymbolPolicy) -> Result<(), String>
+{
+ let symbol = match fsp
+ {
+  FieldSymbolPolicy::Prefix(prefix) => format!("{}_field", prefix),
+  FieldSymbolPolicy::Suffix(suffix) => format!("field_{}", suffix),
+  FieldSymbolPolicy::Fullname(fullname) => fullname.clone(),
+  FieldSymbolPolicy::Default | FieldSymbolPolicy::Bare => return Ok(())
+ };
+ syn::parse_str::<syn::Ident>(&symbol)
+  .map(|_| ())
+  .map_err(|_| symbol)
 }
 
 fn parse_tia_param_syn_expr_type(e_type: &syn::ExprType) -> (TiaParamToken, TiaParamToken)
@@ -375,18 +1030,7 @@ fn parse_tia_param_syn_expr_type(e_type: &syn::ExprType) -> (TiaParamToken, TiaP
    .expr
    .as_ref()
   {
-   syn::Expr::Path(e_path) =>
-   {
-    TiaParamToken::TraitSymbol(
-     e_path
-      .path
-      .segments
-      .first()
-      .unwrap()
-      .ident
-      .to_string()
-    )
-   },
+   syn::Expr::Path(e_path) => TiaParamToken::TraitSymbol(trait_symbol::from_path(&e_path.path)),
    _ => panic!(r#"tia syntax error; Check around of `XXX:` (trait symbol pattern), maybe. #TIA-PANIC-1010"#)
   },
   match e_type
@@ -412,15 +1056,24 @@ fn parse_tia_param_syn_expr_type(e_type: &syn::ExprType) -> (TiaParamToken, TiaP
 
 fn parse_tia_param_syn_expr_path(e_path: &syn::ExprPath) -> TiaParamToken
 {
- TiaParamToken::DefaultDirective(
-  e_path
-   .path
-   .segments
-   .first()
-   .unwrap()
-   .ident
-   .to_string()
- )
+ let key = e_path
+  .path
+  .segments
+  .first()
+  .unwrap()
+  .ident
+  .to_string();
+ match key.as_str()
+ {
+  DIRTY => TiaParamToken::Dirty(key),
+  REFLECT => TiaParamToken::Reflect,
+  VISITOR => TiaParamToken::Visitor(VisitorPolicy::Closure),
+  LENS => TiaParamToken::Lens("crate".to_string()),
+  MERGE => TiaParamToken::Merge(None),
+  SKIP => TiaParamToken::Skip(vec![]),
+  PRINT | EMIT_FILE | DISABLED => TiaParamToken::Output(key),
+  _ => TiaParamToken::DefaultDirective(key)
+ }
 }
 
 fn parse_tia_param_syn_expr_assign_op(e_assign_op: &syn::ExprAssignOp) -> TiaParamToken
@@ -510,6 +1163,47 @@ fn parse_tia_param_syn_expr_assign(e_assign: &syn::ExprAssign) -> TiaParamToken
     {
      match &right_part.lit
      {
+      syn::Lit::Str(right_str) if key == NAMING => TiaParamToken::NamingStyle(parse_naming_style(&right_str.value())),
+      syn::Lit::Str(right_str) if key == DEFINE_TRAIT =>
+      {
+       let (trait_symbol, definition) = trait_definition::parse_trait_definition(&right_str.value());
+       TiaParamToken::TraitDefinition {
+        trait_symbol,
+        definition
+       }
+      },
+      syn::Lit::Str(right_str) if key == DELEGATE => TiaParamToken::Delegations(delegate::parse_delegations(right_str)),
+      syn::Lit::Str(right_str) if key == DELEGATE_FN => TiaParamToken::Accessor(delegate::parse_delegate_fn(right_str)),
+      syn::Lit::Str(right_str) if key == DIRTY => TiaParamToken::Dirty(right_str.value()),
+      syn::Lit::Str(right_str) if key == MERGE =>
+      {
+       TiaParamToken::Merge(Some(trait_symbol::from_lit_str(right_str).unwrap_or_else(|e| panic!("{}", e))))
+      },
+      syn::Lit::Str(right_str) if key == PRESET => TiaParamToken::Preset(right_str.value()),
+      syn::Lit::Str(right_str) if key == EXCLUDE =>
+      {
+       TiaParamToken::Exclude(
+        right_str
+         .value()
+         .split(COMMA)
+         .map(|field_symbol| {
+          field_symbol
+           .trim()
+           .to_string()
+         })
+         .filter(|field_symbol| !field_symbol.is_empty())
+         .collect()
+       )
+      },
+      syn::Lit::Str(right_str) if key == LENS => TiaParamToken::Lens(right_str.value()),
+      syn::Lit::Str(right_str) if key == VISITOR => TiaParamToken::Visitor(VisitorPolicy::Trait(right_str.value())),
+      syn::Lit::Str(right_str) if key == ON_CHANGE || key == ON_CHANGE_IF_CHANGED =>
+      {
+       TiaParamToken::OnChange(OnChange {
+        method:         right_str.value(),
+        skip_unchanged: key == ON_CHANGE_IF_CHANGED
+       })
+      },
       syn::Lit::Str(right_str) =>
       {
        TiaParamToken::CustomDirective {
@@ -541,3 +1235,13 @@ fn parse_tia_param_syn_expr_assign(e_assign: &syn::ExprAssign) -> TiaParamToken
   }
  }
 }
+
+fn parse_naming_style(value: &str) -> NamingStyle
+{
+ match value
+ {
+  "get_set" => NamingStyle::GetSet,
+  "bare" => NamingStyle::Bare,
+  _ => panic!(r#"tia syntax error; Check around of `naming="..."`, supported styles are "get_set" and "bare". #TIA-PANIC-1019"#)
+ }
+}
@@ -0,0 +1,255 @@
+use super::*;
+use quote::ToTokens;
+
+/// `s(validate="check", min=1)` => `TiaParamToken::CallDirective { key: "s", arguments: [("validate", "check"), ("min", "1")] }`
+pub(super) fn parse(e_call: &syn::ExprCall) -> TiaParamToken
+{
+ let key = match e_call
+  .func
+  .as_ref()
+ {
+  syn::Expr::Path(e_path)
+   if e_path
+    .path
+    .get_ident()
+    .is_some() =>
+  {
+   e_path
+    .path
+    .segments
+    .first()
+    .unwrap()
+    .ident
+    .to_string()
+  },
+  _ => panic!(r#"tia syntax error; Check around of `key(...)` (key part of a tia directive with arguments), maybe. #TIA-PANIC-1028"#)
+ };
+
+ match &key[..]
+ {
+  FOR_TYPE => for_type::parse(&e_call.args),
+  SKIP => parse_skip(&e_call.args),
+  _ =>
+  {
+   TiaParamToken::CallDirective {
+    key,
+    arguments: parse_arguments(&e_call.args)
+   }
+  },
+ }
+}
+
+/// `skip(g, "Trait")` => `TiaParamToken::Skip([Accessor(getter), Trait("Trait")])`
+fn parse_skip<'a>(args: impl IntoIterator<Item = &'a syn::Expr>) -> TiaParamToken
+{
+ let skip_targets = args
+  .into_iter()
+  .map(|arg| {
+   let skip_target = match arg
+   {
+    syn::Expr::Path(e_path) =>
+    {
+     e_path
+      .path
+      .get_ident()
+      .and_then(|ident| translate_accessor(&ident.to_string(), FieldSymbolPolicy::Default))
+      .map(SkipTarget::Accessor)
+    },
+    syn::Expr::Lit(syn::ExprLit {
+     lit: syn::Lit::Str(lit_str),
+     ..
+    }) =>
+    {
+     trait_symbol::from_lit_str(lit_str)
+      .ok()
+      .map(SkipTarget::Trait)
+    },
+    _ => None
+   };
+   skip_target.unwrap_or_else(|| {
+    panic!(
+     r#"tia syntax error; Check around of `skip(...)`, the arguments should be the accessor directives or the trait symbols such as `skip(g, "Trait")`. #TIA-PANIC-1054"#
+    )
+   })
+  })
+  .collect();
+ TiaParamToken::Skip(skip_targets)
+}
+
+/// `validate="check", min=1` => `[("validate", "check"), ("min", "1")]`
+pub fn parse_arguments<'a>(args: impl IntoIterator<Item = &'a syn::Expr>) -> Vec<(String, String)>
+{
+ args
+  .into_iter()
+  .map(|arg| {
+   match arg
+   {
+    syn::Expr::Assign(e_assign) =>
+    {
+     let name = match e_assign
+      .left
+      .as_ref()
+     {
+      syn::Expr::Path(e_path)
+       if e_path
+        .path
+        .get_ident()
+        .is_some() =>
+      {
+       e_path
+        .path
+        .segments
+        .first()
+        .unwrap()
+        .ident
+        .to_string()
+      },
+      _ => panic!(r#"tia syntax error; Check around of `key(name=value)` (name part of an argument), maybe. #TIA-PANIC-1029"#)
+     };
+     let value = match e_assign
+      .right
+      .as_ref()
+     {
+      syn::Expr::Lit(syn::ExprLit {
+       lit: syn::Lit::Str(lit_str),
+       ..
+      }) => lit_str.value(),
+      right =>
+      {
+       right
+        .to_token_stream()
+        .to_string()
+      },
+     };
+     (name, value)
+    },
+    _ => panic!(r#"tia syntax error; Check around of `key(name=value)`, the arguments should be `name=value` pairs. #TIA-PANIC-1030"#)
+   }
+  })
+  .collect()
+}
+
+/// `key` such as `s` with `arguments` such as `[("validate", "check")]` => `Accessor`
+pub fn translate(key: &str, arguments: Vec<(String, String)>) -> Accessor
+{
+ let mut fsp = FieldSymbolPolicy::Default;
+ let mut svp = SetterValidationPolicy::default();
+ let mut into_type = None;
+
+ for (name, value) in arguments
+ {
+  match &name[..]
+  {
+   "prefix" => fsp = FieldSymbolPolicy::Prefix(value),
+   "suffix" => fsp = FieldSymbolPolicy::Suffix(value),
+   "fullname" => fsp = FieldSymbolPolicy::Fullname(value),
+   "validate" => svp.validate = Some(value),
+   "min" => svp.min = Some(value),
+   "max" => svp.max = Some(value),
+   "len" => svp.len = Some(value),
+   "error" => svp.error = Some(value),
+   "ty" => into_type = Some(value),
+   _ =>
+   {
+    panic!(
+     r#"tia syntax error; `{}({}=...)` is not supported, the supported arguments are prefix, suffix, fullname, validate, min, max, len, error and ty. #TIA-PANIC-1031"#,
+     key, name
+    )
+   }
+  }
+ }
+
+ if let Err(symbol) = check_field_symbol_policy(&fsp)
+ {
+  panic!(
+   r#"tia syntax error; `{}` of `{}(...)` is not an identifier, it could not be an accessor symbol. #TIA-PANIC-1066"#,
+   symbol, key
+  )
+ }
+ if let Some(Err(e)) = into_type
+  .as_deref()
+  .map(syn::parse_str::<syn::Type>)
+ {
+  panic!(
+   r#"tia syntax error; `{}(ty="{}")` is not a type. ({}) #TIA-PANIC-1067"#,
+   key,
+   into_type.unwrap_or_default(),
+   e
+  )
+ }
+
+ let has_validation = svp != SetterValidationPolicy::default();
+
+ match translate_accessor(key, fsp)
+ {
+  Some(Accessor::Setter {
+   ptp: SetterParameterTypePolicy::TryInto | SetterParameterTypePolicy::Parse,
+   ..
+  })
+   if has_validation =>
+  {
+   panic!(
+    r#"tia syntax error; `{}(validate=...)` and the other checks are not available for the fallible setters `rsti` and `rsp`. #TIA-PANIC-1048"#,
+    key
+   )
+  },
+  Some(Accessor::Setter {
+   fsp,
+   ptp: SetterParameterTypePolicy::AsRef(_),
+   ..
+  }) =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp: SetterParameterTypePolicy::AsRef(into_type),
+    svp: Some(svp).filter(|_| has_validation)
+   }
+  },
+  Some(Accessor::Setter {
+   fsp,
+   ptp,
+   ..
+  })
+   if into_type.is_none() =>
+  {
+   Accessor::Setter {
+    fsp,
+    ptp,
+    svp: Some(svp).filter(|_| has_validation)
+   }
+  },
+  Some(Accessor::Getter {
+   fsp,
+   ptp: GetterParameterTypePolicy::Into(_)
+  })
+   if !has_validation =>
+  {
+   Accessor::Getter {
+    fsp,
+    ptp: GetterParameterTypePolicy::Into(into_type.unwrap_or_default())
+   }
+  },
+  Some(_) if into_type.is_some() =>
+  {
+   panic!(
+    r#"tia syntax error; `{}(ty=...)` is available for `gi` and `rsa` only. #TIA-PANIC-1047"#,
+    key
+   )
+  },
+  Some(_) if has_validation =>
+  {
+   panic!(
+    r#"tia syntax error; `{}(validate=...)` and the other checks are available for the setters only. #TIA-PANIC-1032"#,
+    key
+   )
+  },
+  Some(accessor) => accessor,
+  None =>
+  {
+   panic!(
+    r#"tia syntax error; Check around of `{}(...)`, maybe you wrote an unsupported keyword or typo. #TIA-PANIC-1033"#,
+    key
+   )
+  }
+ }
+}
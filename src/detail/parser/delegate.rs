@@ -0,0 +1,141 @@
+use super::*;
+use quote::ToTokens;
+
+/// The modules of `std` and `core` of the supported traits of `delegate="..."`.
+const DELEGATE_MODULES: [(&str, &str); 21] = [
+ ("Display", "fmt"),
+ ("Debug", "fmt"),
+ ("Binary", "fmt"),
+ ("Octal", "fmt"),
+ ("LowerHex", "fmt"),
+ ("UpperHex", "fmt"),
+ ("LowerExp", "fmt"),
+ ("UpperExp", "fmt"),
+ ("Pointer", "fmt"),
+ ("Iterator", "iter"),
+ ("DoubleEndedIterator", "iter"),
+ ("ExactSizeIterator", "iter"),
+ ("Deref", "ops"),
+ ("DerefMut", "ops"),
+ ("AsRef", "convert"),
+ ("AsMut", "convert"),
+ ("Hash", "hash"),
+ ("PartialEq", "cmp"),
+ ("Eq", "cmp"),
+ ("PartialOrd", "cmp"),
+ ("Ord", "cmp")
+];
+const STD_CRATES: [&str; 2] = ["std", "core"];
+
+/// `"Display, std::iter::Iterator"` => `["Display", "Iterator"]`
+/// The path is compared as written; `fmt::Display`, `std::fmt::Display` and `::core::fmt::Display` are `Display`, but `my::Display` is an error.
+pub fn parse_delegations(lit_str: &syn::LitStr) -> Vec<String>
+{
+ let parser = syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated;
+ lit_str
+  .parse_with(parser)
+  .unwrap_or_else(|e| {
+   panic!(
+    r#"tia syntax error; Check around of `delegate="..."`, it should be like `delegate="Display, Iterator"`. ({}) #TIA-PANIC-1024"#,
+    e
+   )
+  })
+  .iter()
+  .map(|path| {
+   let segments = path
+    .segments
+    .iter()
+    .map(|segment| {
+     segment
+      .ident
+      .to_string()
+    })
+    .collect::<Vec<String>>();
+   let (symbol, modules) = segments
+    .split_last()
+    .expect("tia implementation bug: an empty path was parsed. #TIA-PANIC-1025");
+   let module = DELEGATE_MODULES
+    .iter()
+    .find(|(supported, _)| supported == symbol)
+    .map(|(_, module)| *module);
+   let is_std_path = match (module, modules)
+   {
+    // An unsupported trait is reported by the generator with the supported traits.
+    (None, _) | (_, []) => true,
+    (Some(module), [m]) => m == module,
+    (Some(module), [c, m]) => STD_CRATES.contains(&c.as_str()) && m == module,
+    _ => false
+   };
+   if !is_std_path
+   {
+    panic!(
+     r#"tia syntax error; `delegate="{}"` is not a trait of `std` or `core`, the supported traits are `{}` of `std::{}` or `core::{}`. Use `delegate_fn="..."` for the other traits. #TIA-PANIC-1068"#,
+     path
+      .to_token_stream()
+      .to_string()
+      .replace(' ', ""),
+     symbol,
+     module.unwrap_or_default(),
+     module.unwrap_or_default()
+    )
+   }
+   symbol.clone()
+  })
+  .collect()
+}
+
+/// `"fn len(&self) -> usize"` => `Accessor::Delegate`
+pub fn parse_delegate_fn(lit_str: &syn::LitStr) -> Accessor
+{
+ let value = lit_str.value();
+ let signature = syn::parse_str::<syn::Signature>(value.trim_end_matches(|c: char| c == ';' || c.is_whitespace())).unwrap_or_else(|e| {
+  panic!(
+   r#"tia syntax error; Check around of `delegate_fn="..."`, it should be a fn signature such as `delegate_fn="fn len(&self) -> usize"`. ({}) #TIA-PANIC-1026"#,
+   e
+  )
+ });
+
+ let arguments = signature
+  .inputs
+  .iter()
+  .filter_map(|input| {
+   match input
+   {
+    syn::FnArg::Receiver(_) => None,
+    syn::FnArg::Typed(pat_type) =>
+    {
+     match pat_type
+      .pat
+      .as_ref()
+     {
+      syn::Pat::Ident(pat_ident) =>
+      {
+       Some(
+        pat_ident
+         .ident
+         .to_string()
+       )
+      },
+      _ =>
+      {
+       panic!(
+        r#"tia syntax error; Check around of `delegate_fn="..."`, the parameters should be named such as `v: T`, not a pattern. #TIA-PANIC-1027"#
+       )
+      }
+     }
+    },
+   }
+  })
+  .collect::<Vec<_>>()
+  .join(COMMA);
+
+ Accessor::Delegate {
+  fn_symbol: signature
+   .ident
+   .to_string(),
+  signature: signature
+   .to_token_stream()
+   .to_string(),
+  arguments
+ }
+}
@@ -0,0 +1,67 @@
+use super::*;
+use syn::parse::Parser;
+
+/// `x: f32` or `inner.z: f32`, a member of `flatten(rg="x: f32, inner.z: f32")`.
+struct FlattenMember
+{
+ path: Vec<syn::Ident>,
+ ty:   syn::Type
+}
+
+impl Parse for FlattenMember
+{
+ fn parse(input: ParseStream) -> syn::Result<Self>
+ {
+  let mut path = vec![input.parse::<syn::Ident>()?];
+  while input.peek(syn::Token![.])
+  {
+   input.parse::<syn::Token![.]>()?;
+   path.push(input.parse()?);
+  }
+  input.parse::<syn::Token![:]>()?;
+  Ok(FlattenMember {
+   path,
+   ty: input.parse()?
+  })
+ }
+}
+
+/// `flatten(rg="x: f32, y: f32", s="x: f32")` => `[Flatten]` of the current trait.
+pub fn parse_flattens(arguments: Vec<(String, String)>, trait_symbol: &TraitSymbol) -> Vec<Flatten>
+{
+ let parser = syn::punctuated::Punctuated::<FlattenMember, syn::Token![,]>::parse_terminated;
+ arguments
+  .into_iter()
+  .flat_map(|(key, value)| {
+   let accessor = translate_accessor(&key, FieldSymbolPolicy::Default).unwrap_or_else(|| {
+    panic!(
+     r#"tia syntax error; `flatten({}=...)` is not supported, the key should be an accessor directive such as `rg` or `s`. #TIA-PANIC-1040"#,
+     key
+    )
+   });
+   parser
+    .parse_str(&value)
+    .unwrap_or_else(|e| {
+     panic!(
+      r#"tia syntax error; Check around of `flatten({}="...")`, it should be like `flatten({}="x: f32, inner.z: f32")`. ({}) #TIA-PANIC-1039"#,
+      key, key, e
+     )
+    })
+    .into_iter()
+    .map(move |member| {
+     Flatten {
+      trait_symbol: trait_symbol.clone(),
+      accessor:     accessor.clone(),
+      path:         member
+       .path
+       .iter()
+       .map(|ident| ident.to_string())
+       .collect::<Vec<String>>()
+       .join("."),
+      field_type:   stringify::decode_type(&member.ty)
+     }
+    })
+    .collect::<Vec<Flatten>>()
+  })
+  .collect()
+}
@@ -0,0 +1,221 @@
+use super::*;
+use syn::parse::Parser;
+
+/// `for_type(bool="g", "Vec<_>"="rmg, s")` => `TiaParamToken::ForType`
+pub(super) fn parse<'a>(args: impl IntoIterator<Item = &'a syn::Expr>) -> TiaParamToken
+{
+ let rules = args
+  .into_iter()
+  .map(|arg| {
+   match arg
+   {
+    syn::Expr::Assign(e_assign) => (parse_pattern(&e_assign.left), parse_directives(&e_assign.right)),
+    _ => panic!(r#"tia syntax error; Check around of `for_type(...)`, the arguments should be like `bool="g"` or `"Vec<_>"="rmg, s"`. #TIA-PANIC-1049"#)
+   }
+  })
+  .collect();
+ TiaParamToken::ForType(rules)
+}
+
+/// `bool` or `"Vec<_>"` => `bool` or `Vec < _ >`
+fn parse_pattern(left: &syn::Expr) -> FieldType
+{
+ let pattern = match left
+ {
+  syn::Expr::Lit(syn::ExprLit {
+   lit: syn::Lit::Str(lit_str),
+   ..
+  }) => lit_str.value(),
+  left =>
+  {
+   left
+    .to_token_stream()
+    .to_string()
+  },
+ };
+ syn::parse_str::<syn::Type>(&pattern)
+  .unwrap_or_else(|e| {
+   panic!(
+    r#"tia syntax error; `for_type({}=...)`, the type pattern should be a type such as `bool` or `"Vec<_>"`. ({}) #TIA-PANIC-1062"#,
+    pattern, e
+   )
+  })
+  .to_token_stream()
+  .to_string()
+}
+
+/// `"rmg, s"` => `[rmg, s]`
+fn parse_directives(right: &syn::Expr) -> TiaParamTokenStream
+{
+ let directives = match right
+ {
+  syn::Expr::Lit(syn::ExprLit {
+   lit: syn::Lit::Str(lit_str),
+   ..
+  }) => lit_str.value(),
+  _ => panic!(r#"tia syntax error; `for_type(...=...)`, the directives should be a string such as `"rmg, s"`. #TIA-PANIC-1063"#)
+ };
+ syn::punctuated::Punctuated::<TiaParamSyntax, syn::Token![,]>::parse_terminated
+  .parse_str(&directives)
+  .and_then(translate_tia_param_syntaxes)
+  .unwrap_or_else(|e| {
+   panic!(
+    r#"tia syntax error; Check around of `for_type(...="{}")`. ({}) #TIA-PANIC-1064"#,
+    directives, e
+   )
+  })
+}
+
+/// The rules of `for_type(...)` written after `trait_symbol` => `[ForType]`
+pub(super) fn translate(rules: Vec<(FieldType, TiaParamTokenStream)>, trait_symbol: &TraitSymbol, config: &config::Config) -> Vec<ForType>
+{
+ rules
+  .into_iter()
+  .map(|(pattern, directives)| {
+   let directives = preset::expand(directives, config);
+   // The accessor directives and the trait symbols only.
+   if let Some(directive) = directives.iter().find(|directive| {
+    !matches!(
+     directive,
+     TiaParamToken::TraitSymbol(_) | TiaParamToken::DefaultDirective(_) | TiaParamToken::CustomDirective { .. } | TiaParamToken::Accessor(_)
+    ) && !matches!(directive, TiaParamToken::CallDirective { key, .. } if key != FLATTEN)
+   })
+   {
+    panic!(
+     r#"tia syntax error; `for_type({}=...)` accepts the accessor directives and the trait symbols only, but found `{:?}`. #TIA-PANIC-1050"#,
+     pattern, directive
+    )
+   }
+   let mut tokens = vec![TiaParamToken::TraitSymbol(trait_symbol.clone())];
+   tokens.extend(directives);
+   let accessors = translate_tia_params(tokens, Scope::Field(&mut TargetField::default()), config)
+    .into_iter()
+    .flat_map(|(t, aa)| aa.into_iter().map(move |a| (t.clone(), a)))
+    .collect();
+   ForType {
+    pattern,
+    accessors
+   }
+  })
+  .collect()
+}
+
+/// `Vec<_>` matches `Vec<u8>` and `Vec<Vec<u8>>`, `_` matches any type.
+pub fn matches(pattern: &str, ty: &syn::Type) -> bool
+{
+ syn::parse_str::<syn::Type>(pattern)
+  .map(|pattern| matches_type(&pattern, ty))
+  .unwrap_or_default()
+}
+
+fn matches_type(pattern: &syn::Type, ty: &syn::Type) -> bool
+{
+ match (pattern, ty)
+ {
+  (syn::Type::Infer(_), _) => true,
+  (syn::Type::Path(p), syn::Type::Path(t))
+   if p
+    .qself
+    .is_none()
+    && t
+     .qself
+     .is_none() =>
+  {
+   p.path
+    .segments
+    .len()
+    == t
+     .path
+     .segments
+     .len()
+    && p
+     .path
+     .segments
+     .iter()
+     .zip(
+      &t
+       .path
+       .segments
+     )
+     .all(|(p, t)| p.ident == t.ident && matches_arguments(&p.arguments, &t.arguments))
+  },
+  (syn::Type::Reference(p), syn::Type::Reference(t)) => p.mutability == t.mutability && matches_type(&p.elem, &t.elem),
+  (syn::Type::Slice(p), syn::Type::Slice(t)) => matches_type(&p.elem, &t.elem),
+  (syn::Type::Array(p), syn::Type::Array(t)) =>
+  {
+   p.len
+    .to_token_stream()
+    .to_string()
+    == t
+     .len
+     .to_token_stream()
+     .to_string()
+    && matches_type(&p.elem, &t.elem)
+  },
+  (syn::Type::Tuple(p), syn::Type::Tuple(t)) =>
+  {
+   p.elems
+    .len()
+    == t
+     .elems
+     .len()
+    && p
+     .elems
+     .iter()
+     .zip(&t.elems)
+     .all(|(p, t)| matches_type(p, t))
+  },
+  (syn::Type::Paren(p), _) => matches_type(&p.elem, ty),
+  (_, syn::Type::Paren(t)) => matches_type(pattern, &t.elem),
+  _ =>
+  {
+   pattern
+    .to_token_stream()
+    .to_string()
+    == ty
+     .to_token_stream()
+     .to_string()
+  },
+ }
+}
+
+fn matches_arguments(pattern: &syn::PathArguments, arguments: &syn::PathArguments) -> bool
+{
+ match (pattern, arguments)
+ {
+  (syn::PathArguments::AngleBracketed(p), syn::PathArguments::AngleBracketed(a)) =>
+  {
+   p.args
+    .len()
+    == a
+     .args
+     .len()
+    && p
+     .args
+     .iter()
+     .zip(&a.args)
+     .all(|(p, a)| {
+      match (p, a)
+      {
+       (syn::GenericArgument::Type(p), syn::GenericArgument::Type(a)) => matches_type(p, a),
+       (p, a) =>
+       {
+        p.to_token_stream()
+         .to_string()
+         == a
+          .to_token_stream()
+          .to_string()
+       },
+      }
+     })
+  },
+  (p, a) =>
+  {
+   p.to_token_stream()
+    .to_string()
+    == a
+     .to_token_stream()
+     .to_string()
+  },
+ }
+}
@@ -0,0 +1,63 @@
+use super::*;
+use syn::parse::Parser;
+
+/// `preset="pod"` => the directives of `[presets] pod = "g, s, rsi"` in `tia.toml`, recursively.
+pub(super) fn expand(tokens: TiaParamTokenStream, config: &config::Config) -> TiaParamTokenStream
+{
+ expand_with(tokens, config, &mut vec![])
+}
+
+/// `expanding` is the stack of the preset names to find a cycle such as `a = "preset=\"b\""` and `b = "preset=\"a\""`.
+fn expand_with(tokens: TiaParamTokenStream, config: &config::Config, expanding: &mut Vec<String>) -> TiaParamTokenStream
+{
+ tokens
+  .into_iter()
+  .flat_map(|token| {
+   match token
+   {
+    TiaParamToken::Preset(name) =>
+    {
+     if expanding.contains(&name)
+     {
+      panic!(
+       r#"tia syntax error: `preset="{}"` is recursive; {} -> {}. #TIA-PANIC-1058"#,
+       name,
+       expanding.join(" -> "),
+       name
+      )
+     }
+     let directives = config
+      .presets
+      .get(&name)
+      .unwrap_or_else(|| {
+       let mut names = config
+        .presets
+        .keys()
+        .cloned()
+        .collect::<Vec<String>>();
+       names.sort();
+       panic!(
+        r#"tia syntax error: `preset="{}"` is not found in `[presets]` of `tia.toml`; the presets are [{}]. #TIA-PANIC-1056"#,
+        name,
+        names.join(", ")
+       )
+      });
+     let tokens = syn::punctuated::Punctuated::<TiaParamSyntax, syn::Token![,]>::parse_terminated
+      .parse_str(directives)
+      .and_then(translate_tia_param_syntaxes)
+      .unwrap_or_else(|e| {
+       panic!(
+        r#"tia syntax error: Check around of `[presets] {} = "{}"`. ({}) #TIA-PANIC-1057"#,
+        name, directives, e
+       )
+      });
+     expanding.push(name);
+     let tokens = expand_with(tokens, config, expanding);
+     expanding.pop();
+     tokens
+    },
+    token => vec![token]
+   }
+  })
+  .collect()
+}
@@ -63,8 +63,7 @@ pub fn decode_type(ty: &syn::Type) -> String
     path,
     delimiters.0,
     m.mac
-     .tokens
-     .to_string(),
+     .tokens,
     delimiters.1
    )
   },
@@ -85,14 +84,7 @@ pub fn decode_type(ty: &syn::Type) -> String
     .path
     .segments
     .iter()
-    .map(|s| {
-     format!(
-      "{}{}",
-      s.ident
-       .to_string(),
-      decode_type_path_arguments(&s.arguments)
-     )
-    })
+    .map(|s| format!("{}{}", s.ident, decode_type_path_arguments(&s.arguments)))
     .collect::<Vec<_>>()
     .join(COLON_COLON_SEPARATOR)
   },
@@ -114,14 +106,14 @@ pub fn decode_type(ty: &syn::Type) -> String
    let lifetime = re
     .lifetime
     .as_ref()
-    .map(|lifetime| format!("{} ", lifetime.to_string()))
+    .map(|lifetime| format!("{} ", lifetime))
     .unwrap_or_default();
    let m = re
     .mutability
     .map(|_| "mut ".to_string())
     .unwrap_or_default();
    let elem = decode_type(
-    &re
+    re
      .elem
      .as_ref()
    );
@@ -146,7 +138,7 @@ pub fn decode_type(ty: &syn::Type) -> String
     "({})",
     t.elems
      .iter()
-     .map(|t| decode_type(t))
+     .map(decode_type)
      .collect::<Vec<_>>()
      .join(COMMA)
    )
@@ -194,7 +186,7 @@ pub fn decode_type_path_arguments(arguments: &syn::PathArguments) -> String
    let i = parenthesized
     .inputs
     .iter()
-    .map(|ty| decode_type(ty))
+    .map(decode_type)
     .collect::<Vec<_>>()
     .join(COMMA);
    let o = decode_return_type(&parenthesized.output);
@@ -0,0 +1,104 @@
+use super::*;
+use quote::ToTokens;
+use syn::parse::{
+ Parse,
+ ParseStream
+};
+
+/// `pub FooAccess<T: Clone>: Debug + Default where T: Default` part of the `define_trait="..."` directive.
+struct TraitDeclaration
+{
+ visibility:  syn::Visibility,
+ ident:       syn::Ident,
+ generics:    syn::Generics,
+ supertraits: syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>
+}
+
+impl Parse for TraitDeclaration
+{
+ fn parse(input: ParseStream) -> syn::Result<Self>
+ {
+  let visibility = input.parse()?;
+  let ident = input.parse()?;
+  let mut generics: syn::Generics = input.parse()?;
+  let mut supertraits = syn::punctuated::Punctuated::new();
+  if input
+   .parse::<Option<syn::Token![:]>>()?
+   .is_some()
+  {
+   while !input.is_empty() && !input.peek(syn::Token![where])
+   {
+    supertraits.push_value(input.parse()?);
+    match input.parse::<Option<syn::Token![+]>>()?
+    {
+     Some(plus) => supertraits.push_punct(plus),
+     None => break
+    }
+   }
+  }
+  generics.where_clause = input.parse()?;
+  Ok(TraitDeclaration {
+   visibility,
+   ident,
+   generics,
+   supertraits
+  })
+ }
+}
+
+/// Returns ( trait symbol for the `impl` header, trait definition ).
+pub fn parse_trait_definition(source: &str) -> (TraitSymbol, TraitDefinition)
+{
+ let d = syn::parse_str::<TraitDeclaration>(source).unwrap_or_else(|e| {
+  panic!(
+   r#"tia syntax error; Check around of `define_trait="..."`, it should be like `define_trait="pub MyTrait<T>: SuperTrait"`. ({}) #TIA-PANIC-1021"#,
+   e
+  )
+ });
+
+ let (impl_generics, ty_generics, where_clause) = d
+  .generics
+  .split_for_impl();
+
+ let trait_path = syn::parse_str::<syn::Path>(&format!("{}{}", d.ident, ty_generics.to_token_stream()))
+  .expect("tia implementation bug: failed to build a trait path from the `define_trait`. #TIA-PANIC-2002");
+ let trait_symbol = trait_symbol::from_path(&trait_path);
+
+ let supertraits = match d
+  .supertraits
+  .is_empty()
+ {
+  true => String::default(),
+  false =>
+  {
+   format!(
+    ":{}",
+    d.supertraits
+     .to_token_stream()
+   )
+  }
+ };
+ let where_clause = where_clause
+  .map(|w| w.to_token_stream())
+  .unwrap_or_default()
+  .to_string();
+
+ let declaration_header = format!(
+  "{} trait {}{}{} {}",
+  d.visibility
+   .to_token_stream(),
+  d.ident,
+  d.generics
+   .to_token_stream(),
+  supertraits,
+  where_clause
+ );
+
+ (trait_symbol, TraitDefinition {
+  declaration_header,
+  impl_generics: impl_generics
+   .to_token_stream()
+   .to_string(),
+  where_clause
+ })
+}
@@ -0,0 +1,47 @@
+use super::*;
+use quote::ToTokens;
+
+/// `crate::api::Getter::<i32>` => `crate :: api :: Getter < i32 >`, the key of a `TraitToFieldAccessors`.
+pub fn from_path(path: &syn::Path) -> TraitSymbol
+{
+ let mut path = path.clone();
+ for segment in path
+  .segments
+  .iter_mut()
+ {
+  if let syn::PathArguments::AngleBracketed(arguments) = &mut segment.arguments
+  {
+   arguments.colon2_token = None;
+  }
+ }
+ path
+  .to_token_stream()
+  .to_string()
+}
+
+/// `"crate::api::Getter<i32>"` => `crate :: api :: Getter < i32 >`, `""` => `` ( no trait ).
+pub fn from_lit_str(lit_str: &syn::LitStr) -> syn::Result<TraitSymbol>
+{
+ match lit_str
+  .value()
+  .trim()
+  .is_empty()
+ {
+  true => Ok(TraitSymbol::default()),
+  false =>
+  {
+   lit_str
+    .parse::<syn::Path>()
+    .map(|path| from_path(&path))
+    .map_err(|e| {
+     syn::Error::new(
+      lit_str.span(),
+      format!(
+       r#"tia syntax error; Check around of `"TraitSymbol"`, it should be a trait path such as `"crate::api::Getter<i32>"`. ({}) #TIA-ERROR-1022"#,
+       e
+      )
+     )
+    })
+  },
+ }
+}
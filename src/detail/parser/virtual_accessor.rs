@@ -0,0 +1,46 @@
+use super::*;
+
+/// `virtual(g="area", ty="f64", expr="self.w * self.h")` => `TiaParamToken::VirtualAccessor`
+pub(super) fn parse(arguments: Vec<(String, String)>) -> TiaParamToken
+{
+ let mut accessor = None;
+ let mut field_type = None;
+ let mut expr = None;
+
+ for (name, value) in arguments
+ {
+  match &name[..]
+  {
+   "ty" => field_type = Some(value),
+   "expr" => expr = Some(value),
+   key =>
+   {
+    let translated = translate_accessor(key, FieldSymbolPolicy::Fullname(value)).filter(|_| accessor.is_none());
+    accessor = Some(translated.unwrap_or_else(|| {
+     panic!(
+      r#"tia syntax error; `virtual({}=...)` is not supported, the arguments should be an accessor directive such as `g="area"`, `ty="..."` and `expr="..."`. #TIA-PANIC-1043"#,
+      key
+     )
+    }));
+   }
+  }
+ }
+
+ match (accessor, field_type, expr)
+ {
+  (Some(accessor), Some(field_type), Some(expr)) =>
+  {
+   TiaParamToken::VirtualAccessor {
+    accessor,
+    field_type,
+    expr
+   }
+  },
+  _ =>
+  {
+   panic!(
+    r#"tia syntax error; `virtual(...)` requires an accessor directive, `ty` and `expr` such as `virtual(g="area", ty="f64", expr="self.w * self.h")`. #TIA-PANIC-1044"#
+   )
+  }
+ }
+}
@@ -126,39 +126,112 @@ pub fn derive_tia(s: pm::TokenStream) -> pm::TokenStream
   let target_type_symbol = i
    .ident
    .to_string();
-  let cargo_root_path = std::env::var("CARGO_MANIFEST_DIR").expect("");
-  let file_path = std::path::Path::new(&cargo_root_path)
-   .join("src")
-   .join(".tia")
-   .join(format!("{}.rs", target_type_symbol));
-  let file_path = std::path::Path::new(&file_path);
+  let input_hash = input_hash(&i, &config::config());
+  let file_path = generated_file(&target_type_symbol, &input_hash).path;
+  let file_path = file_path.as_path();
   let include_path = file_path
    .to_string_lossy()
    .replace("\\", "/");
-  if file_path.exists()
+  match include_state(file_path, &input_hash)
   {
-   eprintln!(
-    "[proc-macro:tia +include|+include-force Target: {} => no generate, instead include!. ]",
-    target_type_symbol
-   );
-   let r = format!(r#"include!("{}");"#, include_path);
-   let r = r.parse::<pm::TokenStream>();
-   return r.expect("tia +include|+include-force feature, tia::into<proc_macro::TokenStream> was failed. #TIA-PANIC-5001");
+   IncludeState::UpToDate =>
+   {
+    eprintln!(
+     "[proc-macro:tia +include|+include-force Target: {} => no generate, instead include!. ]",
+     target_type_symbol
+    );
+    // The generated file does not track `tia.toml`, thus the rebuild tracking is added to the token stream.
+    let r = format!(
+     r#"include!("{}");{}"#,
+     include_path,
+     config::generate_config_dependency(&config::config())
+    );
+    let r = r.parse::<pm::TokenStream>();
+    return r.expect("tia +include|+include-force feature, tia::into<proc_macro::TokenStream> was failed. #TIA-PANIC-5001");
+   },
+   // The type or the directives are changed after the file was generated.
+   IncludeState::Stale(file_hash) =>
+   {
+    eprintln!(
+     "[proc-macro:tia +include|+include-force Target: {} => the include file is stale.; input hash = {}, file hash = {}, path = {} ]",
+     target_type_symbol, input_hash, file_hash, include_path
+    );
+    if cfg!(feature = "include-force")
+    {
+     return stale_include_error(&i, &include_path);
+    }
+   },
+   IncludeState::NoInputHash =>
+   {
+    eprintln!(
+     "[proc-macro:tia +include|+include-force Target: {} => the include file has no input hash.; path = {} ]",
+     target_type_symbol, include_path
+    );
+    if cfg!(feature = "include-force")
+    {
+     return stale_include_error(&i, &include_path);
+    }
+   },
+   IncludeState::NotFound =>
+   {
+    eprintln!(
+     "[proc-macro:tia +include|+include-force Target: {} => file not found (It's not an error, yet).; expected path = {} ]",
+     target_type_symbol, include_path
+    );
+    if cfg!(feature = "include-force")
+    {
+     panic!("tia +include-force feature, but the include file is not found. #TIA-PANIC-5002");
+    }
+   }
   }
-  eprintln!(
-   "[proc-macro:tia +include|+include-force Target: {} => file not found (It's not an error, yet).; expected path = {} ]",
-   target_type_symbol, include_path
-  );
-  if cfg!(feature = "include-force")
+ }
+
+ match Tia::new(i)
+ {
+  Ok(tia) => tia.into(),
+  Err(e) =>
   {
-   panic!("tia +include-force feature, but the include file is not found. #TIA-PANIC-5002");
-  }
+   e.to_compile_error()
+    .into()
+  },
  }
+}
 
- let tia = Tia::new(i);
- tia.into()
+/// `compile_error!` at the type; `include-force` never regenerates the file, thus the user should regenerate it.
+#[cfg(not(feature = "disable"))]
+fn stale_include_error(i: &syn::DeriveInput, include_path: &str) -> pm::TokenStream
+{
+ syn::Error::new(
+  i.ident
+   .span(),
+  format!(
+   "tia +include-force feature, but the include file `{}` is stale; the type or the tia directives were changed after it was generated. \
+    Regenerate it with the `include` feature or `TIA_EMIT_FILE` please. #TIA-PANIC-5003",
+   include_path
+  )
+ )
+ .to_compile_error()
+ .into()
 }
 
 #[cfg(feature = "disable")]
 #[proc_macro_derive(Tia, attributes(tia))]
 pub fn derive_tia(s: pm::TokenStream) -> pm::TokenStream { pm::TokenStream::default() }
+
+/// Injects the accessors of a trait with the `merge` directive into the `impl` block, eg. `#[tia::impl_accessors] impl Trait for MyStruct { fn custom(&self) {} }`.
+#[cfg(not(feature = "disable"))]
+#[proc_macro_attribute]
+pub fn impl_accessors(_: pm::TokenStream, item: pm::TokenStream) -> pm::TokenStream { detail::impl_accessors(item) }
+
+#[cfg(feature = "disable")]
+#[proc_macro_attribute]
+pub fn impl_accessors(_: pm::TokenStream, item: pm::TokenStream) -> pm::TokenStream { item }
+
+/// Expands the `Lens` trait and the `Then` composition for the `lens` directive; write it once in a module such as the crate root.
+#[cfg(not(feature = "disable"))]
+#[proc_macro]
+pub fn lens_types(_: pm::TokenStream) -> pm::TokenStream { detail::lens_types() }
+
+#[cfg(feature = "disable")]
+#[proc_macro]
+pub fn lens_types(_: pm::TokenStream) -> pm::TokenStream { pm::TokenStream::default() }
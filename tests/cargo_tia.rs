@@ -0,0 +1,251 @@
+use std::{
+ path::{
+  Path,
+  PathBuf
+ },
+ process::Output
+};
+
+fn run(manifest_dir: &Path, command: &str) -> Output
+{
+ std::process::Command::new(env!("CARGO_BIN_EXE_cargo-tia"))
+  .args(["tia", command, "--manifest-path"])
+  .arg(manifest_dir.join("Cargo.toml"))
+  .output()
+  .unwrap()
+}
+
+fn write_crate(manifest_dir: &Path, lib: &str)
+{
+ write_crate_with(manifest_dir, "[package]\nname = \"sample-app\"\nversion = \"0.1.0\"\n", lib)
+}
+
+fn write_crate_with(manifest_dir: &Path, manifest: &str, lib: &str)
+{
+ std::fs::create_dir_all(manifest_dir.join("src")).unwrap();
+ std::fs::write(manifest_dir.join("Cargo.toml"), manifest).unwrap();
+ std::fs::write(manifest_dir.join("src/lib.rs"), lib).unwrap();
+}
+
+#[test]
+fn expand_check_list_clean()
+{
+ let manifest_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cargo_tia");
+ let _ = std::fs::remove_dir_all(&manifest_dir);
+ write_crate(&manifest_dir, "#[derive(tia::Tia)]\n#[tia(rg, s)]\npub struct Point { x: i32 }\n");
+ let generated = manifest_dir.join("src/.tia/sample_app/Point.rs");
+
+ // list
+ let output = run(&manifest_dir, "list");
+ assert!(output
+  .status
+  .success());
+ let stdout = String::from_utf8_lossy(&output.stdout);
+ assert!(stdout.contains("Point (src/lib.rs)"));
+ assert!(stdout.contains("fn get_x"));
+ assert!(stdout.contains("fn set_x"));
+
+ // expand => check
+ assert!(!run(&manifest_dir, "check")
+  .status
+  .success());
+ assert!(run(&manifest_dir, "expand")
+  .status
+  .success());
+ assert!(generated.exists());
+ assert!(run(&manifest_dir, "check")
+  .status
+  .success());
+
+ // The directives are changed => stale
+ write_crate(&manifest_dir, "#[derive(tia::Tia)]\n#[tia(rg)]\npub struct Point { x: i32 }\n");
+ let output = run(&manifest_dir, "check");
+ assert!(!output
+  .status
+  .success());
+ assert!(String::from_utf8_lossy(&output.stdout).contains("stale"));
+
+ // The type is renamed => orphan
+ write_crate(&manifest_dir, "#[derive(tia::Tia)]\n#[tia(rg)]\npub struct Vector { x: i32 }\n");
+ assert!(run(&manifest_dir, "expand")
+  .status
+  .success());
+ let output = run(&manifest_dir, "check");
+ assert!(String::from_utf8_lossy(&output.stdout).contains("orphan"));
+ assert!(run(&manifest_dir, "clean")
+  .status
+  .success());
+ assert!(!generated.exists());
+ assert!(run(&manifest_dir, "check")
+  .status
+  .success());
+
+ std::fs::remove_dir_all(&manifest_dir).unwrap();
+}
+
+/// A syntax error is reported as an error of the type, without the panic message of the hook.
+#[test]
+fn syntax_error()
+{
+ let manifest_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cargo_tia_syntax_error");
+ let _ = std::fs::remove_dir_all(&manifest_dir);
+ write_crate(
+  &manifest_dir,
+  "#[derive(tia::Tia)]\n#[tia(preset=\"missing\")]\npub struct Point { x: i32 }\n"
+ );
+
+ let output = run(&manifest_dir, "list");
+ let stderr = String::from_utf8_lossy(&output.stderr);
+ assert!(!output
+  .status
+  .success());
+ assert!(stderr.contains("error: Point (src/lib.rs): "), "{}", stderr);
+ assert!(stderr.contains("#TIA-PANIC-1056"), "{}", stderr);
+ assert!(!stderr.contains("panicked"), "{}", stderr);
+
+ std::fs::remove_dir_all(&manifest_dir).unwrap();
+}
+
+/// The files generated by the proc-macro in a real build should be `ok` in `cargo tia check`.
+#[test]
+#[cfg_attr(
+ not(tia_local_file),
+ ignore = "the module paths of the generated files require `proc_macro::Span::local_file` (1.88)"
+)]
+fn check_after_build()
+{
+ let tmp_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+ let manifest_dir = tmp_dir.join("cargo_tia_build");
+ let _ = std::fs::remove_dir_all(&manifest_dir);
+ write_crate_with(
+  &manifest_dir,
+  &format!(
+   "[package]\nname = \"interop\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n\n[[test]]\nname = \"suite\"\npath = \
+    \"tests/integration.rs\"\n\n[dependencies]\ntia = {{ path = {:?}, features = [\"file\"] }}\n",
+   env!("CARGO_MANIFEST_DIR")
+  ),
+  r#"
+/// A doc comment is not a part of the input hash.
+#[derive(tia::Tia)]
+#[tia(rg, s)]
+pub struct Point
+{
+ x: i32,
+ #[tia(rmg)]
+ label: String
+}
+
+pub mod inner
+{
+ #[derive(Default, tia::Tia)]
+ #[tia(g)]
+ pub struct Hidden
+ {
+  x: u8,
+  #[cfg(any())]
+  hidden: u8
+ }
+}
+
+// The same-named types in the inline modules are output to `a/Twin.rs` and `b/Twin.rs`.
+pub mod a
+{
+ #[derive(tia::Tia)]
+ #[tia(g)]
+ pub struct Twin
+ {
+  x: u8
+ }
+}
+
+pub mod b
+{
+ #[derive(tia::Tia)]
+ #[tia(g)]
+ pub struct Twin
+ {
+  y: u16
+ }
+}
+
+// `src/tests/mod.rs` is the module `tests`, it is not a crate root such as `tests/test.rs`.
+pub mod tests;
+"#
+ );
+ std::fs::create_dir_all(manifest_dir.join("src/tests")).unwrap();
+ std::fs::write(
+  manifest_dir.join("src/tests/mod.rs"),
+  "#[derive(tia::Tia)]\n#[tia(g)]\npub struct Point\n{\n z: i64\n}\n"
+ )
+ .unwrap();
+ // The crate name of `tests/integration.rs` is `suite` of `[[test]]`, and `tests/common/mod.rs` is a module of the both tests.
+ std::fs::create_dir_all(manifest_dir.join("tests/common")).unwrap();
+ std::fs::write(manifest_dir.join("tests/integration.rs"), "#[allow(dead_code)]\nmod common;\n").unwrap();
+ std::fs::write(manifest_dir.join("tests/other.rs"), "#[allow(dead_code)]\nmod common;\n").unwrap();
+ std::fs::write(
+  manifest_dir.join("tests/common/mod.rs"),
+  "#[derive(tia::Tia)]\n#[tia(g)]\npub struct Shared\n{\n w: u32\n}\n"
+ )
+ .unwrap();
+
+ let status = std::process::Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+  .args(["build", "--offline", "--quiet", "--tests", "--manifest-path"])
+  .arg(manifest_dir.join("Cargo.toml"))
+  .arg("--target-dir")
+  .arg(tmp_dir.join("cargo_tia_build_target"))
+  .env_remove("TIA_PRINT")
+  .env_remove("TIA_EMIT_FILE")
+  .status()
+  .unwrap();
+ assert!(status.success());
+ assert!(manifest_dir
+  .join("src/.tia/interop/Point.rs")
+  .exists());
+ assert!(manifest_dir
+  .join("src/.tia/interop/inner/Hidden.rs")
+  .exists());
+ assert!(std::fs::read_to_string(manifest_dir.join("src/.tia/interop/a/Twin.rs"))
+  .unwrap()
+  .contains("get_x"));
+ assert!(std::fs::read_to_string(manifest_dir.join("src/.tia/interop/b/Twin.rs"))
+  .unwrap()
+  .contains("get_y"));
+ assert!(std::fs::read_to_string(manifest_dir.join("src/.tia/interop/Point.rs"))
+  .unwrap()
+  .contains("get_x"));
+ assert!(std::fs::read_to_string(manifest_dir.join("src/.tia/interop/tests/Point.rs"))
+  .unwrap()
+  .contains("get_z"));
+ for crate_name in ["suite", "other"]
+ {
+  assert!(manifest_dir
+   .join(format!("src/.tia/{}/common/Shared.rs", crate_name))
+   .exists());
+ }
+
+ let output = run(&manifest_dir, "check");
+ let stdout = String::from_utf8_lossy(&output.stdout);
+ assert!(
+  output
+   .status
+   .success(),
+  "{}",
+  stdout
+ );
+ assert!(stdout.contains("ok          Point (src/lib.rs)"));
+ assert!(stdout.contains("ok          Point (src/tests/mod.rs)"));
+ assert_eq!(
+  stdout
+   .matches("ok          Shared (tests/common/mod.rs)")
+   .count(),
+  2,
+  "{}",
+  stdout
+ );
+ // The proc-macro removes the cfg'd out field, but `cargo tia` could not evaluate it.
+ assert!(stdout.contains("unsupported Hidden"));
+ assert!(stdout.contains("ok          Twin (src/lib.rs) => "));
+ assert!(!stdout.contains("orphan"));
+
+ std::fs::remove_dir_all(&manifest_dir).unwrap();
+}
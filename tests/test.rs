@@ -1,3 +1,5 @@
+#![allow(dead_code, clippy::ptr_arg)]
+
 use tia::Tia;
 
 trait TestGetter
@@ -29,6 +31,374 @@ struct S
  w: u8
 }
 
+#[derive(Tia, Default)]
+#[tia(naming = "bare", rg, s)]
+struct B
+{
+ x: i32,
+ #[tia(rmg)]
+ y: String,
+ #[tia(gm)]
+ z: Vec<u8>
+}
+
+#[derive(Tia, Default, Debug)]
+#[tia(define_trait = "pub DefinedAccess<T>: std::fmt::Debug where T: Default", rg, s)]
+struct D
+{
+ x: i32,
+ #[tia("DefinedAccess<T>", g)]
+ y: u8
+}
+
+mod api
+{
+ pub trait Getter<T>
+ {
+  fn get_x(&self) -> T;
+ }
+
+ pub mod nested
+ {
+  pub trait RefGetter
+  {
+   fn get_y(&self) -> &String;
+  }
+
+  pub trait Setter
+  {
+   fn set_y(&mut self, v: String);
+  }
+ }
+}
+
+#[derive(Tia, Default)]
+struct P
+{
+ #[tia(trait = api::Getter<i32>, g)]
+ x: i32,
+ #[tia("api::nested::RefGetter", rg, api::nested::Setter: s)]
+ y: String
+}
+
+#[derive(Tia, Default)]
+struct Envelope
+{
+ #[tia(gm*="into_payload")]
+ payload: Vec<u8>,
+ #[tia(gm = "take")]
+ header:  String,
+ #[tia(gm+="owned")]
+ footer:  String
+}
+
+fn is_even(v: &u16) -> Result<(), String>
+{
+ match v % 2
+ {
+  0 => Ok(()),
+  _ => Err("odd".to_string())
+ }
+}
+
+#[derive(Debug, PartialEq)]
+struct ConfigError(String);
+
+impl From<&'static str> for ConfigError
+{
+ fn from(s: &'static str) -> Self { ConfigError(s.to_string()) }
+}
+
+#[derive(Tia, Default)]
+#[tia(rg)]
+struct Config
+{
+ #[tia(s(validate = "is_even", min = 1024, max = "u16::MAX - 1"))]
+ port: u16,
+ #[tia(rsi(len = "1..=8"))]
+ name: String,
+ #[tia(rsc(len = 2, error = "ConfigError", suffix = "checked"))]
+ code: String
+}
+
+trait Length
+{
+ fn len(&self) -> usize;
+}
+
+#[derive(Tia)]
+struct Meters
+{
+ #[tia(delegate = "Display, PartialEq, PartialOrd, Deref", delegate_fn = "fn abs(&self) -> f64")]
+ v: f64
+}
+
+#[derive(Tia)]
+struct Bytes
+{
+ #[tia(
+  delegate = "std::fmt::Debug, Iterator, ExactSizeIterator",
+  "Length",
+  delegate_fn = "fn len(&self) -> usize"
+ )]
+ inner: std::vec::IntoIter<u8>
+}
+
+#[derive(Tia, Default)]
+#[tia(on_change = "notify", s, rsc)]
+struct ViewModel
+{
+ #[tia(rsi, rmg)]
+ title: String,
+ #[tia(on_change_if_changed = "notify", s)]
+ count: u32,
+ log:   std::cell::RefCell<Vec<String>>
+}
+
+impl ViewModel
+{
+ fn notify<T: std::fmt::Debug + ?Sized>(&self, field: ViewModelField, old: &T, new: &T)
+ {
+  self
+   .log
+   .borrow_mut()
+   .push(format!("{:?}:{:?}->{:?}", field, old, new));
+ }
+}
+
+#[derive(Tia, Default)]
+#[tia(dirty = "changes", s)]
+struct Record
+{
+ #[tia(s, rmg)]
+ id:      u32,
+ name:    String,
+ note:    String,
+ changes: RecordDirty
+}
+
+#[derive(Tia, Default)]
+#[tia(reflect)]
+struct Inspectable
+{
+ #[tia(rg, s)]
+ width:  u32,
+ #[tia(rg, s(min = 1))]
+ height: u32,
+ #[tia(s)]
+ secret: String
+}
+
+trait FieldVisitor
+{
+ fn visit<T: std::fmt::Debug>(&mut self, name: &str, value: &T);
+ fn visit_mut<T: std::fmt::Debug>(&mut self, name: &str, value: &mut T);
+}
+
+struct Dump(Vec<String>);
+
+impl FieldVisitor for Dump
+{
+ fn visit<T: std::fmt::Debug>(&mut self, name: &str, value: &T)
+ {
+  self
+   .0
+   .push(format!("{}={:?}", name, value));
+ }
+
+ fn visit_mut<T: std::fmt::Debug>(&mut self, name: &str, value: &mut T)
+ {
+  self
+   .0
+   .push(format!("{}:{:?}", name, value));
+ }
+}
+
+#[derive(Tia, Default)]
+#[tia(visitor)]
+struct Visited
+{
+ #[tia(rg, rmg)]
+ a:      u8,
+ #[tia(g)]
+ b:      u16,
+ secret: u32
+}
+
+#[derive(Tia, Default)]
+#[tia(visitor = "FieldVisitor", rg)]
+struct TraitVisited
+{
+ #[tia(rmg)]
+ x: i32,
+ y: String
+}
+
+tia::lens_types!();
+
+#[derive(Tia, Default)]
+#[tia(lens)]
+struct Position
+{
+ x: f32,
+ y: f32
+}
+
+#[derive(Tia, Default)]
+#[tia(lens = "crate")]
+struct Player
+{
+ pub name:     String,
+ pub position: Position
+}
+
+#[derive(Default)]
+struct Body
+{
+ pos:  Position,
+ mass: f64
+}
+
+#[derive(Tia, Default)]
+struct Facade
+{
+ #[tia(flatten(rg = "pos.x: f32, pos.y: f32, mass: f64", s = "mass: f64"))]
+ body: Body,
+ #[tia(rg)]
+ name: String
+}
+
+trait Shape
+{
+ fn get_w(&self) -> f64;
+ fn get_h(&self) -> f64;
+ fn area(&self) -> f64;
+}
+
+#[derive(Tia, Default)]
+#[tia(
+ "Shape",
+ g,
+ virtual(g = "area", ty = "f64", expr = "self.w * self.h"),
+ "",
+ virtual(s = "set_square", ty = f64, expr = "self.w = v; self.h = v")
+)]
+struct Rect
+{
+ w: f64,
+ h: f64
+}
+
+trait Account
+{
+ fn get_id(&self) -> u64;
+ fn get_owner(&self) -> &String;
+ fn label(&self) -> String;
+}
+
+// The `impl` could be before the type, and the trait path is matched by its last segments.
+#[tia::impl_accessors]
+impl self::Account for User
+{
+ fn label(&self) -> String { format!("{}#{}", self.get_owner(), self.get_id()) }
+}
+
+#[derive(Tia, Default)]
+#[tia(merge, merge = "Account", "Account", g)]
+struct User
+{
+ id:    u64,
+ #[tia("Account", rg, "", rsi)]
+ owner: String
+}
+
+#[tia::impl_accessors]
+impl User
+{
+ pub fn new(id: u64) -> Self
+ {
+  User {
+   id,
+   ..Default::default()
+  }
+ }
+}
+
+#[derive(Tia, Default)]
+struct Ticket
+{
+ #[tia(gi(ty = u64))]
+ id:    u32,
+ #[tia(rgc)]
+ title: String
+}
+
+#[derive(Tia, Default)]
+struct Endpoint
+{
+ #[tia(rsti)]
+ port:  u16,
+ #[tia(rsa)]
+ path:  std::path::PathBuf,
+ #[tia(rsa(ty = str))]
+ name:  String,
+ #[tia(rsp)]
+ ratio: f64
+}
+
+#[derive(Tia, Default)]
+#[tia(rg, for_type(bool = "g", String = "rg, rsi", "Vec<_>" = "rmg"))]
+struct Form
+{
+ agreed:  bool,
+ name:    String,
+ tags:    Vec<String>,
+ scores:  Vec<u8>,
+ #[tia(g)]
+ retries: u8
+}
+
+trait Secret
+{
+ fn secret_user(&self) -> &String;
+}
+
+#[derive(Tia, Default)]
+#[tia(reflect, rg, s, "Secret", rg = "secret", exclude = "token")]
+struct Login
+{
+ user:     String,
+ #[tia(skip)]
+ password: String,
+ #[tia(skip(s, "Secret"))]
+ hint:     String,
+ #[tia(skip, s)]
+ pin:      u32,
+ token:    String
+}
+
+#[derive(Tia, Default)]
+#[tia(preset = "pod")]
+struct Sample
+{
+ x:     i32,
+ y:     i32,
+ #[tia(preset = "settings")]
+ label: String
+}
+
+#[derive(Tia, Default)]
+#[tia(disabled, rg)]
+struct Disabled
+{
+ x: i32
+}
+
+impl Disabled
+{
+ fn get_x(&self) -> i32 { self.x + 1 }
+}
+
 #[derive(Tia)]
 union U
 {
@@ -58,6 +428,369 @@ fn r#struct()
  assert_eq!(p.get_w(), 123u8);
 }
 
+#[test]
+fn naming_bare()
+{
+ let mut p = B::default();
+
+ p.set_x(123i32);
+ assert_eq!(p.x(), &123i32);
+
+ p.y_mut()
+  .push_str("foobar");
+ p.set_y("hogefuga".to_string());
+ assert_eq!(p.y_mut(), "hogefuga");
+
+ p.set_z(vec![1u8, 2u8]);
+ assert_eq!(p.into_z(), vec![1u8, 2u8]);
+}
+
+#[test]
+fn define_trait()
+{
+ fn access<A: DefinedAccess<()>>(a: &mut A) -> (i32, u8)
+ {
+  a.set_x(123i32);
+  a.set_y(45u8);
+  (*a.get_x(), a.get_y())
+ }
+
+ let mut p = D::default();
+ assert_eq!(access(&mut p), (123i32, 45u8));
+}
+
+#[test]
+fn trait_path()
+{
+ use api::{
+  nested::*,
+  Getter
+ };
+
+ let mut p = P {
+  x: 123i32,
+  ..Default::default()
+ };
+ p.set_y("foobar".to_string());
+ assert_eq!(p.get_x(), 123i32);
+ assert_eq!(p.get_y(), "foobar");
+}
+
+#[test]
+fn move_getter_naming()
+{
+ let e = Envelope {
+  payload: vec![1u8],
+  ..Default::default()
+ };
+ assert_eq!(e.into_payload(), vec![1u8]);
+
+ let e = Envelope {
+  header: "foo".to_string(),
+  footer: "bar".to_string(),
+  ..Default::default()
+ };
+ assert_eq!(e.take_header(), "foo");
+
+ let e = Envelope {
+  footer: "bar".to_string(),
+  ..Default::default()
+ };
+ assert_eq!(e.footer_owned(), "bar");
+}
+
+#[test]
+fn validated_setter()
+{
+ let mut c = Config::default();
+
+ assert_eq!(c.set_port(8080u16), Ok(()));
+ assert_eq!(c.set_port(80u16), Err("port is less than the min 1024".to_string()));
+ assert_eq!(c.set_port(u16::MAX), Err("port is greater than the max u16::MAX - 1".to_string()));
+ assert_eq!(c.set_port(8081u16), Err("odd".to_string()));
+ assert_eq!(c.get_port(), &8080u16);
+
+ assert_eq!(c.set_name("tia"), Ok(()));
+ assert!(c
+  .set_name("")
+  .is_err());
+ assert!(c
+  .set_name("too long name")
+  .is_err());
+ assert_eq!(c.get_name(), "tia");
+
+ assert_eq!(c.code_checked(&"jp".to_string()), Ok(()));
+ assert_eq!(
+  c.code_checked(&"jpn".to_string()),
+  Err(ConfigError("code.len() is out of the range 2".to_string()))
+ );
+ assert_eq!(c.get_code(), "jp");
+}
+
+#[test]
+fn delegate()
+{
+ let a = Meters {
+  v: -1.5f64
+ };
+ let b = Meters {
+  v: 2.0f64
+ };
+ assert_eq!(a.to_string(), "-1.5");
+ assert!(a < b);
+ assert_eq!(*a, -1.5f64);
+ assert_eq!(a.abs(), 1.5f64);
+
+ let bytes = Bytes {
+  inner: vec![1u8, 2u8, 3u8].into_iter()
+ };
+ assert_eq!(Length::len(&bytes), 3usize);
+ assert_eq!(format!("{:?}", bytes), format!("{:?}", vec![1u8, 2u8, 3u8].into_iter()));
+ assert_eq!(bytes.collect::<Vec<_>>(), vec![1u8, 2u8, 3u8]);
+}
+
+#[test]
+fn on_change()
+{
+ let mut m = ViewModel::default();
+ m.set_title("a");
+ *m.get_title() += "b";
+ m.set_count(0u32);
+ m.set_count(1u32);
+ assert_eq!(
+  m.log
+   .take(),
+  vec![
+   r#"Title:""->"a""#.to_string(),
+   r#"Title:"a"->"ab""#.to_string(),
+   "Count:0->1".to_string(),
+  ]
+ );
+ assert_eq!(ViewModelField::Log, ViewModelField::Log);
+}
+
+#[test]
+fn dirty()
+{
+ let mut r = Record::default();
+ assert!(r
+  .dirty_fields()
+  .is_empty());
+
+ r.set_name("foo".to_string());
+ *r.get_id() += 1;
+ assert!(r.is_dirty_id());
+ assert!(r.is_dirty_name());
+ assert!(!r.is_dirty_note());
+ assert_eq!(r.dirty_fields(), vec![RecordField::Id, RecordField::Name]);
+
+ r.clear_dirty();
+ assert!(r
+  .dirty_fields()
+  .is_empty());
+}
+
+#[test]
+fn reflect()
+{
+ assert_eq!(Inspectable::field_names(), &["width", "height", "secret"]);
+ assert_eq!(InspectableField::from_name("height"), Some(InspectableField::Height));
+ assert_eq!(InspectableField::ALL[2].name(), "secret");
+
+ let mut i = Inspectable::default();
+ assert!(i
+  .set_by_name("width", Box::new(3u32))
+  .is_ok());
+ assert!(i
+  .set_by_name("width", Box::new("3"))
+  .is_err());
+ assert!(i
+  .set_by_name("height", Box::new(3u32))
+  .is_err());
+ assert!(i
+  .set_by_name("secret", Box::new("foo".to_string()))
+  .is_ok());
+ assert_eq!(
+  i.get_by_name("width")
+   .and_then(|v| v.downcast_ref::<u32>()),
+  Some(&3u32)
+ );
+ assert!(i
+  .get_by_name("secret")
+  .is_none());
+ assert!(i
+  .get_by_name("nothing")
+  .is_none());
+}
+
+#[test]
+fn visitor()
+{
+ let mut v = Visited::default();
+ let mut names = vec![];
+ v.visit_fields(&mut |name, value| names.push((name, value.is::<u8>())));
+ assert_eq!(names, vec![("a", true), ("b", false)]);
+ v.visit_fields_mut(&mut |_, value| {
+  *value
+   .downcast_mut::<u8>()
+   .unwrap() = 7u8
+ });
+ assert_eq!(*v.get_a(), 7u8);
+
+ let mut t = TraitVisited {
+  x: 1i32,
+  y: "foo".to_string()
+ };
+ let mut dump = Dump(vec![]);
+ t.visit_fields(&mut dump);
+ t.visit_fields_mut(&mut dump);
+ assert_eq!(dump.0, vec!["x=1", r#"y="foo""#, "x:1"]);
+}
+
+#[test]
+fn lens()
+{
+ let mut p = Player::default();
+ let name = Player::lens_name();
+ name.set(&mut p, "foo".to_string());
+ assert_eq!(name.get(&p), "foo");
+
+ let y = Player::lens_position().then(Position::lens_y());
+ *y.get_mut(&mut p) += 2.0f32;
+ let doubled = y.get(&p) * 2.0f32;
+ y.set(&mut p, doubled);
+ assert_eq!(
+  p.position
+   .y,
+  4.0f32
+ );
+ assert_eq!(
+  p.position
+   .x,
+  0.0f32
+ );
+}
+
+#[test]
+fn flatten()
+{
+ let mut f = Facade::default();
+ f.body
+  .pos
+  .x = 1.0f32;
+ f.set_mass(2.0f64);
+ assert_eq!(f.get_x(), &1.0f32);
+ assert_eq!(f.get_y(), &0.0f32);
+ assert_eq!(f.get_mass(), &2.0f64);
+ assert_eq!(f.get_name(), "");
+}
+
+#[test]
+fn r#virtual()
+{
+ let mut r = Rect::default();
+ r.set_square(3.0f64);
+ let shape = &r as &dyn Shape;
+ assert_eq!(shape.get_w(), 3.0f64);
+ assert_eq!(shape.area(), 9.0f64);
+}
+
+#[test]
+fn impl_accessors()
+{
+ let mut u = User::new(7u64);
+ u.set_owner("foo");
+ assert_eq!(u.label(), "foo#7");
+}
+
+#[test]
+fn converted_getter()
+{
+ let t = Ticket {
+  id:    7u32,
+  title: "foo".to_string()
+ };
+ assert_eq!(t.get_id(), 7u64);
+ assert_eq!(t.get_title(), "foo".to_string());
+}
+
+#[test]
+fn converting_setter()
+{
+ let mut c = Endpoint::default();
+ assert!(c
+  .set_port(8080u32)
+  .is_ok());
+ assert!(c
+  .set_port(-1i32)
+  .is_err());
+ assert_eq!(c.port, 8080u16);
+ c.set_path("/tmp/foo");
+ assert_eq!(c.path, std::path::Path::new("/tmp/foo"));
+ c.set_name("foo");
+ assert_eq!(c.name, "foo");
+ assert!(c
+  .set_ratio_str("0.5")
+  .is_ok());
+ assert!(c
+  .set_ratio_str("bar")
+  .is_err());
+ assert_eq!(c.ratio, 0.5f64);
+}
+
+#[test]
+fn for_type()
+{
+ let mut f = Form::default();
+ f.set_name("foo");
+ f.get_tags()
+  .push("bar".to_string());
+ f.get_scores()
+  .push(1u8);
+ assert!(!f.get_agreed());
+ assert_eq!(f.get_name(), "foo");
+ assert_eq!(f.tags, ["bar".to_string()]);
+ assert_eq!(f.scores, [1u8]);
+ assert_eq!(f.get_retries(), 0u8);
+}
+
+#[test]
+fn skip()
+{
+ let mut l = Login::default();
+ l.set_user("foo".to_string());
+ l.set_pin(1234u32);
+ assert_eq!(l.get_user(), "foo");
+ assert_eq!(l.get_hint(), "");
+ assert_eq!(Secret::secret_user(&l), "foo");
+ assert_eq!(Login::FIELDS, &["user", "hint"]);
+ assert!(l
+  .get_by_name("password")
+  .is_none());
+ assert!(l
+  .set_by_name("pin", Box::new(0u32))
+  .is_err());
+ assert_eq!(l.pin, 1234u32);
+}
+
+#[test]
+fn preset()
+{
+ let mut s = Sample::default();
+ s.set_x(1);
+ s.set_y(2);
+ s.set_label("foo");
+ assert_eq!(s.get_x() + s.get_y(), 3);
+ assert_eq!(s.get_label(), "foo");
+}
+
+#[test]
+fn disabled()
+{
+ let d = Disabled::default();
+ assert_eq!(d.get_x(), 1);
+}
+
 #[test]
 fn r#union()
 {